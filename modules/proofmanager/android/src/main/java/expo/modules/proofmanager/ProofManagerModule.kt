@@ -22,11 +22,12 @@ class ProofManagerModule : Module() {
     }
 
     // Native function declarations - need to be at class level
+    // Addresses are maps of diversifier, transmissionKey and clueKey bytes
     private external fun createProofNative(
-        seedPhrase: String,
+        debtorAddress: Map<String, ByteArray>,
+        creditorAddress: Map<String, ByteArray>,
         amount: Long,
-        assetId: Long,
-        addressIndex: Int
+        assetId: Long
     ): Map<String, ByteArray>
 
 
@@ -34,22 +35,73 @@ class ProofManagerModule : Module() {
 
     private external fun generateAddressNative(seedPhrase: String, index: Int): Map<String, ByteArray>
 
+    // Circuits are numbered as in ProofCircuit: 0 note opening, 1 authorization, 2 intent
+    private external fun loadProvingKeyNative(circuit: Int, provingKey: ByteArray)
+
+    private external fun loadVerifyingKeyNative(circuit: Int, verifyingKey: ByteArray)
+
+    // Ceremony keys bundled as assets, indexed by circuit number
+    private val circuitNames = listOf("note_opening", "authorization", "intent")
+
+    // Release builds have no Groth16 keys until they are loaded, so load the
+    // bundled ones before anything can sign or verify
+    private fun loadBundledKeys() {
+        val assets = appContext.reactContext?.assets ?: return
+        circuitNames.forEachIndexed { circuit, name ->
+            try {
+                assets.open("proving_keys/$name.pk").use {
+                    loadProvingKeyNative(circuit, it.readBytes())
+                }
+                assets.open("proving_keys/$name.vk").use {
+                    loadVerifyingKeyNative(circuit, it.readBytes())
+                }
+            } catch (e: java.io.FileNotFoundException) {
+                println("Kotlin: No bundled keys for $name, expecting loadProvingKey")
+            }
+        }
+    }
+
+    private fun toAddressBytes(address: Any?): Map<String, ByteArray> {
+        val fields = address as Map<*, *>
+        return listOf("diversifier", "transmissionKey", "clueKey").associateWith { key ->
+            (fields[key] as List<*>).map { (it as Number).toByte() }.toByteArray()
+        }
+    }
+
     override fun definition() = ModuleDefinition {
         Name("ProofManager")
 
+        OnCreate {
+            loadBundledKeys()
+        }
+
+        AsyncFunction("loadProvingKey") { circuit: Int, provingKey: List<Int> ->
+            try {
+                loadProvingKeyNative(circuit, provingKey.map { it.toByte() }.toByteArray())
+            } catch (e: Exception) {
+                throw Error("Failed to load proving key: ${e.message}")
+            }
+        }
+
+        AsyncFunction("loadVerifyingKey") { circuit: Int, verifyingKey: List<Int> ->
+            try {
+                loadVerifyingKeyNative(circuit, verifyingKey.map { it.toByte() }.toByteArray())
+            } catch (e: Exception) {
+                throw Error("Failed to load verifying key: ${e.message}")
+            }
+        }
+
         AsyncFunction("createProof") { input: Map<String, Any> ->
         try {
             val result = createProofNative(
-                seedPhrase = input["seedPhrase"] as String,
+                debtorAddress = toAddressBytes(input["debtorAddress"]),
+                creditorAddress = toAddressBytes(input["creditorAddress"]),
                 amount = (input["amount"] as Number).toLong(),
-                assetId = (input["assetId"] as Number).toLong(),
-                addressIndex = (input["addressIndex"] as Number).toInt()
-            )
-        
-            mapOf(
-                "proof" to result["proof"]?.map { it.toInt() and 0xFF },
-                "commitment" to result["commitment"]?.map { it.toInt() and 0xFF }
+                assetId = (input["assetId"] as Number).toLong()
             )
+
+            // Without the opening the commitment cannot be spent or checked later
+            result.mapValues { (_, bytes) -> bytes.map { it.toInt() and 0xFF } }
             } catch (e: Exception) {
                 throw Error("Failed to create proof: ${e.message}")
             }
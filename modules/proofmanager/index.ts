@@ -7,7 +7,9 @@ export const {
   generateAddress,
   createNote,
   signNote,
-  verifySignature
+  verifySignature,
+  loadProvingKey,
+  loadVerifyingKey
 } = ProofManagerModule;
 
 export default ProofManagerModule;
\ No newline at end of file
@@ -1,20 +1,31 @@
 // ProofManager.types.ts
+export interface AddressInfo {
+  diversifier: number[];
+  transmissionKey: number[];
+  clueKey: number[];
+}
+
 export interface ProofInput {
-  seedPhrase: string;
+  debtorAddress: AddressInfo;
+  creditorAddress: AddressInfo;
   amount: number;
   assetId: number;
-  addressIndex: number;
 }
 
+// The proof and the full note opening it proves; amount and assetId are
+// little-endian u64 bytes, and the rseed must be stored and kept secret
 export interface ProofResult {
   proof: number[];
   commitment: number[];
-}
-
-export interface AddressInfo {
-  diversifier: number[];
-  transmissionKey: number[];
-  clueKey: number[];
+  amount: number[];
+  assetId: number[];
+  rseed: number[];
+  debtorDiversifier: number[];
+  debtorTransmissionKey: number[];
+  debtorClueKey: number[];
+  creditorDiversifier: number[];
+  creditorTransmissionKey: number[];
+  creditorClueKey: number[];
 }
 
 export interface IntentAction {
@@ -24,19 +35,33 @@ export interface IntentAction {
   zkp: number[];
   noteCiphertext: number[];
   auxCiphertext: number[];
+  // FMD clue for the creditor, empty when none was requested
+  clue: number[];
+}
+
+// Numbered as the native ProofCircuit enum
+export enum ProofCircuit {
+  NoteOpening = 0,
+  Authorization = 1,
+  Intent = 2,
 }
 
 export interface ProofManagerInterface {
+  // Release builds must load ceremony keys before signing or verifying
+  loadProvingKey(circuit: ProofCircuit, provingKey: number[]): Promise<void>;
+  loadVerifyingKey(circuit: ProofCircuit, verifyingKey: number[]): Promise<void>;
   createProof(input: ProofInput): Promise<ProofResult>;
   verifyProof(proof: number[], commitment: number[]): Promise<boolean>;
   generateAddress(seedPhrase: string, index: number): Promise<AddressInfo>;
 
   createIntentAction(
     seedPhrase: string,
+    bip44Account: number,
     amount: number,
     assetId: number,
     addressIndex: number,
-    creditorAddress: AddressInfo
+    creditorAddress: AddressInfo,
+    cluePrecision?: number
 ): Promise<IntentAction>;
 
     verifyIntentAction(
@@ -1,6 +1,14 @@
-import { ProofManagerInterface, ProofInput, SerializedProof, AddressInfo } from './ProofManager.types';
+import { ProofManagerInterface, ProofCircuit, ProofInput, SerializedProof, AddressInfo } from './ProofManager.types';
 
 class WebProofManager implements ProofManagerInterface {
+  async loadProvingKey(circuit: ProofCircuit, provingKey: number[]): Promise<void> {
+    throw new Error('ProofManager is not supported on web platform');
+  }
+
+  async loadVerifyingKey(circuit: ProofCircuit, verifyingKey: number[]): Promise<void> {
+    throw new Error('ProofManager is not supported on web platform');
+  }
+
   async generateAddress(seed_phrase: string, index: number): Promise<AddressInfo> {
     throw new Error('ProofManager is not supported on web platform');
   }
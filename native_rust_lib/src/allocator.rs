@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Index 0 is the wallet's primary address, so contacts start after it.
+pub const FIRST_CONTACT_INDEX: u32 = 1;
+
+/// Remembers which address index was handed to which counterparty.
+///
+/// Every contact gets an index of its own, so IOUs issued to different
+/// creditors cannot be linked through a shared address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "SavedAllocator")]
+pub struct AddressAllocator {
+    next_index: u32,
+    contacts: BTreeMap<String, u32>,
+}
+
+/// Saved state, checked before it becomes an [`AddressAllocator`].
+#[derive(Deserialize)]
+struct SavedAllocator {
+    next_index: u32,
+    contacts: BTreeMap<String, u32>,
+}
+
+impl TryFrom<SavedAllocator> for AddressAllocator {
+    type Error = String;
+
+    /// Refuses state that would hand an index out twice: an index outside
+    /// `FIRST_CONTACT_INDEX..next_index` would be allocated again later.
+    fn try_from(saved: SavedAllocator) -> Result<Self, Self::Error> {
+        let mut seen = BTreeSet::new();
+        for (contact, index) in &saved.contacts {
+            if !(FIRST_CONTACT_INDEX..saved.next_index).contains(index) {
+                return Err(format!("index {index} of {contact} is outside the allocated range"));
+            }
+            if !seen.insert(*index) {
+                return Err(format!("index {index} is given to more than one contact"));
+            }
+        }
+
+        Ok(Self {
+            next_index: saved.next_index,
+            contacts: saved.contacts,
+        })
+    }
+}
+
+impl Default for AddressAllocator {
+    fn default() -> Self {
+        Self {
+            next_index: FIRST_CONTACT_INDEX,
+            contacts: BTreeMap::new(),
+        }
+    }
+}
+
+impl AddressAllocator {
+    /// The index given to `contact`, allocating the next free one on first use.
+    pub fn allocate(&mut self, contact: &str) -> Option<u32> {
+        if let Some(index) = self.contacts.get(contact) {
+            return Some(*index);
+        }
+
+        let index = self.next_index;
+        self.next_index = index.checked_add(1)?;
+        self.contacts.insert(contact.to_string(), index);
+        Some(index)
+    }
+
+    pub fn index_of(&self, contact: &str) -> Option<u32> {
+        self.contacts.get(contact).copied()
+    }
+
+    pub fn contact_of(&self, index: u32) -> Option<&str> {
+        self.contacts
+            .iter()
+            .find(|(_, i)| **i == index)
+            .map(|(contact, _)| contact.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn contacts_keep_their_own_index() {
+        let mut allocator = AddressAllocator::default();
+        assert_eq!(allocator.allocate("alice"), Some(1));
+        assert_eq!(allocator.allocate("bob"), Some(2));
+        assert_eq!(allocator.allocate("alice"), Some(1));
+        assert_eq!(allocator.contact_of(2), Some("bob"));
+
+        let json = serde_json::to_string(&allocator).unwrap();
+        let mut restored: AddressAllocator = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, allocator);
+        assert_eq!(restored.allocate("carol"), Some(3));
+    }
+
+    #[test]
+    fn refuses_state_that_reuses_an_index() {
+        for state in [
+            r#"{"next_index":2,"contacts":{"alice":1,"bob":2}}"#,
+            r#"{"next_index":3,"contacts":{"alice":1,"bob":1}}"#,
+            r#"{"next_index":3,"contacts":{"alice":0}}"#,
+        ] {
+            assert!(serde_json::from_str::<AddressAllocator>(state).is_err(), "{state}");
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{Bech32m, Hrp};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use blahaj::{Share, Sharks};
+use zeroize::Zeroizing;
+
+/// Human-readable part of every encoded share.
+pub const SHARE_HRP: &str = "pocketshare";
+
+/// Random identifier shared by every share of one split.
+const SPLIT_ID_LEN: usize = 8;
+/// Truncated hash of the secret, checked after recovery.
+const DIGEST_LEN: usize = 16;
+/// threshold || split id || digest
+const HEADER_LEN: usize = 1 + SPLIT_ID_LEN + DIGEST_LEN;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("threshold must be at least 1 and at most the number of shares")]
+    InvalidThreshold,
+    #[error("share is not a valid encoding: {0}")]
+    InvalidEncoding(String),
+    #[error("shares come from different splits")]
+    MismatchedShares,
+    #[error("need {0} shares to recover the secret")]
+    NotEnoughShares(u8),
+    #[error("recovered secret does not match its digest; a share is corrupt")]
+    DigestMismatch,
+}
+
+/// Splits `secret` into `shares` Shamir shares, any `threshold` of which recover it.
+///
+/// Each share is bech32m encoded, so a typo is caught by the checksum
+/// before it can silently corrupt the recovered secret. Every share also
+/// carries the split's identifier and a digest of the secret, so shares of
+/// different splits are refused and a corrupt share cannot go unnoticed.
+pub fn split(secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<String>, Error> {
+    if threshold == 0 || threshold > shares {
+        return Err(Error::InvalidThreshold);
+    }
+
+    let mut split_id = [0u8; SPLIT_ID_LEN];
+    OsRng.fill_bytes(&mut split_id);
+    let digest = digest(&split_id, secret);
+
+    let hrp = Hrp::parse(SHARE_HRP).expect("valid hrp");
+    Sharks(threshold)
+        .dealer_rng(secret, &mut OsRng)
+        .take(shares as usize)
+        .map(|share| {
+            // threshold || split id || digest || x || y
+            let mut data = Zeroizing::new(vec![threshold]);
+            data.extend_from_slice(&split_id);
+            data.extend_from_slice(&digest);
+            data.extend_from_slice(&Zeroizing::new(Vec::from(&share)));
+            bech32::encode::<Bech32m>(hrp, &data).map_err(|e| Error::InvalidEncoding(e.to_string()))
+        })
+        .collect()
+}
+
+/// Recovers the secret from encoded shares produced by [`split`].
+pub fn combine(encoded: &[String]) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let mut header = None;
+    let mut shares = Vec::with_capacity(encoded.len());
+
+    for share in encoded {
+        let checked = CheckedHrpstring::new::<Bech32m>(share.trim())
+            .map_err(|e| Error::InvalidEncoding(e.to_string()))?;
+        if checked.hrp().as_str() != SHARE_HRP {
+            return Err(Error::InvalidEncoding(format!("unexpected prefix {}", checked.hrp())));
+        }
+
+        let data = Zeroizing::new(checked.byte_iter().collect::<Vec<u8>>());
+        if data.len() <= HEADER_LEN {
+            return Err(Error::InvalidEncoding("truncated share".to_string()));
+        }
+        let (share_header, share) = data.split_at(HEADER_LEN);
+        if *header.get_or_insert_with(|| share_header.to_vec()) != share_header {
+            return Err(Error::MismatchedShares);
+        }
+
+        shares.push(Share::try_from(share).map_err(|e| Error::InvalidEncoding(e.to_string()))?);
+    }
+
+    let header = header.ok_or(Error::NotEnoughShares(1))?;
+    let (threshold, split_id, expected_digest) = (
+        header[0],
+        &header[1..1 + SPLIT_ID_LEN],
+        &header[1 + SPLIT_ID_LEN..],
+    );
+    let secret = Sharks(threshold)
+        .recover(&shares)
+        .map(Zeroizing::new)
+        .map_err(|_| Error::NotEnoughShares(threshold))?;
+
+    if digest(split_id, &secret) != expected_digest {
+        return Err(Error::DigestMismatch);
+    }
+    Ok(secret)
+}
+
+fn digest(split_id: &[u8], secret: &[u8]) -> [u8; DIGEST_LEN] {
+    let hash = blake2b_simd::Params::new()
+        .personal(b"pocketlib_Share_")
+        .hash_length(DIGEST_LEN)
+        .to_state()
+        .update(split_id)
+        .update(secret)
+        .finalize();
+    hash.as_bytes().try_into().expect("hash is DIGEST_LEN bytes")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn any_threshold_of_shares_recovers_the_secret() {
+        let secret = [42u8; 32];
+        let shares = split(&secret, 3, 5).unwrap();
+        assert!(shares.iter().all(|share| share.starts_with(SHARE_HRP)));
+
+        let recovered = combine(&shares[2..]).unwrap();
+        assert_eq!(recovered.as_slice(), &secret);
+
+        assert!(matches!(combine(&shares[..2]), Err(Error::NotEnoughShares(3))));
+
+        // A single typo is caught by the checksum
+        let mut typo = shares[0].clone().into_bytes();
+        let last = typo.len() - 1;
+        typo[last] = if typo[last] == b'q' { b'p' } else { b'q' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert!(matches!(combine(&[typo]), Err(Error::InvalidEncoding(_))));
+    }
+
+    #[test]
+    fn rejects_shares_of_another_split() {
+        let secret = [42u8; 32];
+        let first = split(&secret, 2, 3).unwrap();
+        let second = split(&secret, 2, 3).unwrap();
+
+        assert!(matches!(
+            combine(&[first[0].clone(), second[1].clone()]),
+            Err(Error::MismatchedShares)
+        ));
+    }
+
+    #[test]
+    fn detects_a_corrupt_share() {
+        let shares = split(&[42u8; 32], 2, 3).unwrap();
+
+        // Re-encode a share with a flipped byte so its checksum still holds
+        let checked = CheckedHrpstring::new::<Bech32m>(&shares[0]).unwrap();
+        let mut data: Vec<u8> = checked.byte_iter().collect();
+        *data.last_mut().unwrap() ^= 1;
+        let corrupt = bech32::encode::<Bech32m>(Hrp::parse(SHARE_HRP).unwrap(), &data).unwrap();
+
+        assert!(matches!(combine(&[corrupt, shares[1].clone()]), Err(Error::DigestMismatch)));
+    }
+}
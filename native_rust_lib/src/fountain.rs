@@ -0,0 +1,355 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+
+/// Prefix of every encoded part.
+pub const PART_PREFIX: &str = "pocketlib:part/";
+
+/// Most fragments a message is split into. Every mixed part costs the
+/// decoder work proportional to `seq_len`, so it must not be attacker-chosen.
+pub const MAX_SEQ_LEN: u32 = 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("part is malformed: {0}")]
+    Malformed(&'static str),
+    #[error("part belongs to a different message")]
+    MismatchedPart,
+    #[error("reassembled message does not match its checksum")]
+    ChecksumMismatch,
+}
+
+/// One frame of a multi-part transfer.
+///
+/// Parts `1..=seq_len` carry the message fragments in order. Every later
+/// part is the XOR of a pseudo-random subset of fragments, so the receiver
+/// can finish from whichever parts it happens to scan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Part {
+    pub seq_num: u32,
+    pub seq_len: u32,
+    pub message_len: u32,
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    /// `pocketlib:part/<seq_num>-<seq_len>/<base64url(message_len || checksum || data)>`
+    pub fn encode(&self) -> String {
+        let mut body = Vec::with_capacity(8 + self.data.len());
+        body.extend_from_slice(&self.message_len.to_be_bytes());
+        body.extend_from_slice(&self.checksum.to_be_bytes());
+        body.extend_from_slice(&self.data);
+
+        format!(
+            "{PART_PREFIX}{}-{}/{}",
+            self.seq_num,
+            self.seq_len,
+            URL_SAFE_NO_PAD.encode(body)
+        )
+    }
+
+    pub fn decode(part: &str) -> Result<Self, Error> {
+        let rest = part
+            .trim()
+            .strip_prefix(PART_PREFIX)
+            .ok_or(Error::Malformed("missing prefix"))?;
+        let (sequence, body) = rest.split_once('/').ok_or(Error::Malformed("missing body"))?;
+        let (seq_num, seq_len) = sequence
+            .split_once('-')
+            .ok_or(Error::Malformed("missing sequence"))?;
+        let seq_num: u32 = seq_num.parse().map_err(|_| Error::Malformed("invalid seq_num"))?;
+        let seq_len: u32 = seq_len.parse().map_err(|_| Error::Malformed("invalid seq_len"))?;
+        if seq_num == 0 || seq_len == 0 {
+            return Err(Error::Malformed("sequence starts at 1"));
+        }
+        if seq_len > MAX_SEQ_LEN {
+            return Err(Error::Malformed("too many fragments"));
+        }
+
+        let body = URL_SAFE_NO_PAD
+            .decode(body)
+            .map_err(|_| Error::Malformed("invalid base64"))?;
+        if body.len() <= 8 {
+            return Err(Error::Malformed("truncated"));
+        }
+
+        let message_len = u32::from_be_bytes(body[..4].try_into().expect("4 bytes"));
+        let data = body[8..].to_vec();
+        // The encoder splits into as few fragments as the fragment length allows
+        if seq_len as usize != (message_len as usize).div_ceil(data.len()).max(1) {
+            return Err(Error::Malformed("seq_len does not match the message length"));
+        }
+
+        Ok(Self {
+            seq_num,
+            seq_len,
+            message_len,
+            checksum: u32::from_be_bytes(body[4..8].try_into().expect("4 bytes")),
+            data,
+        })
+    }
+}
+
+/// Produces an endless stream of parts for one message.
+pub struct Encoder {
+    fragments: Vec<Vec<u8>>,
+    message_len: u32,
+    checksum: u32,
+    seq_num: u32,
+}
+
+impl Encoder {
+    /// Fragments are at most `max_fragment_len` bytes, unless that would take
+    /// more than [`MAX_SEQ_LEN`] fragments; then they grow to fit.
+    pub fn new(message: &[u8], max_fragment_len: usize) -> Self {
+        let max_fragment_len = max_fragment_len
+            .max(message.len().div_ceil(MAX_SEQ_LEN as usize))
+            .max(1);
+        let seq_len = message.len().div_ceil(max_fragment_len).max(1);
+        // Spread the message evenly so the last fragment is not mostly padding
+        let fragment_len = message.len().div_ceil(seq_len).max(1);
+
+        let fragments = (0..seq_len)
+            .map(|i| {
+                let mut fragment = message
+                    .iter()
+                    .skip(i * fragment_len)
+                    .take(fragment_len)
+                    .copied()
+                    .collect::<Vec<u8>>();
+                fragment.resize(fragment_len, 0);
+                fragment
+            })
+            .collect();
+
+        Self {
+            fragments,
+            message_len: message.len() as u32,
+            checksum: crc32fast::hash(message),
+            seq_num: 0,
+        }
+    }
+
+    pub fn seq_len(&self) -> u32 {
+        self.fragments.len() as u32
+    }
+
+    pub fn next_part(&mut self) -> Part {
+        self.seq_num = self.seq_num.wrapping_add(1).max(1);
+        let indexes = choose_fragments(self.seq_num, self.seq_len(), self.checksum);
+
+        let mut data = vec![0u8; self.fragments[0].len()];
+        for index in indexes {
+            xor_into(&mut data, &self.fragments[index]);
+        }
+
+        Part {
+            seq_num: self.seq_num,
+            seq_len: self.seq_len(),
+            message_len: self.message_len,
+            checksum: self.checksum,
+            data,
+        }
+    }
+}
+
+/// Reassembles a message from parts received in any order.
+#[derive(Default)]
+pub struct Decoder {
+    // seq_len, message_len, checksum and fragment length of the first part
+    expected: Option<(u32, u32, u32, usize)>,
+    simple: BTreeMap<usize, Vec<u8>>,
+    mixed: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    message: Option<Vec<u8>>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a part to the decoder; returns whether the message is complete.
+    ///
+    /// On [`Error::ChecksumMismatch`] one of the parts was corrupt, and there
+    /// is no telling which, so the decoder discards everything and starts over.
+    pub fn receive(&mut self, part: &Part) -> Result<bool, Error> {
+        if self.message.is_some() {
+            return Ok(true);
+        }
+
+        let shape = (part.seq_len, part.message_len, part.checksum, part.data.len());
+        if *self.expected.get_or_insert(shape) != shape {
+            return Err(Error::MismatchedPart);
+        }
+
+        let indexes = choose_fragments(part.seq_num, part.seq_len, part.checksum)
+            .into_iter()
+            .collect();
+        self.add(indexes, part.data.clone());
+
+        if self.simple.len() == part.seq_len as usize {
+            let mut message: Vec<u8> = self.simple.values().flatten().copied().collect();
+            message.truncate(part.message_len as usize);
+            if crc32fast::hash(&message) != part.checksum {
+                *self = Self::default();
+                return Err(Error::ChecksumMismatch);
+            }
+            self.message = Some(message);
+        }
+
+        Ok(self.message.is_some())
+    }
+
+    /// Fraction of fragments recovered so far.
+    pub fn progress(&self) -> f64 {
+        match self.expected {
+            Some((seq_len, ..)) => self.simple.len() as f64 / seq_len as f64,
+            None => 0.0,
+        }
+    }
+
+    pub fn message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+
+    /// Peels known fragments off a part and propagates whatever that reveals.
+    fn add(&mut self, indexes: BTreeSet<usize>, data: Vec<u8>) {
+        let mut queue = vec![(indexes, data)];
+
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            for index in indexes.clone() {
+                if let Some(fragment) = self.simple.get(&index) {
+                    xor_into(&mut data, fragment);
+                    indexes.remove(&index);
+                }
+            }
+
+            match indexes.len() {
+                0 => {}
+                1 => {
+                    let index = *indexes.first().expect("one index");
+                    self.simple.insert(index, data);
+                    // Mixed parts containing the new fragment may now peel further
+                    let (reducible, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.mixed)
+                        .into_iter()
+                        .partition(|(mixed, _)| mixed.contains(&index));
+                    self.mixed = rest;
+                    queue.extend(reducible);
+                }
+                _ => {
+                    if !self.mixed.iter().any(|(mixed, _)| *mixed == indexes) {
+                        self.mixed.push((indexes, data));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The fragments XORed into part `seq_num`; encoder and decoder agree on it.
+fn choose_fragments(seq_num: u32, seq_len: u32, checksum: u32) -> Vec<usize> {
+    if seq_num <= seq_len {
+        return vec![seq_num as usize - 1];
+    }
+
+    let mut seed = [0u8; 32];
+    seed[..4].copy_from_slice(&seq_num.to_be_bytes());
+    seed[4..8].copy_from_slice(&checksum.to_be_bytes());
+    let mut rng = ChaCha20Rng::from_seed(seed);
+
+    // Low degrees are the most likely, as in a soliton distribution
+    let degrees = WeightedIndex::new((1..=seq_len).map(|d| 1.0 / d as f64))
+        .expect("weights are positive");
+    let degree = degrees.sample(&mut rng) + 1;
+
+    rand::seq::index::sample(&mut rng, seq_len as usize, degree).into_vec()
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    for (t, o) in target.iter_mut().zip(other) {
+        *t ^= o;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decodes_from_any_stretch_of_parts() {
+        let message: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+        let mut encoder = Encoder::new(&message, 100);
+        assert_eq!(encoder.seq_len(), 10);
+
+        // Skip the plain fragments entirely: the mixed parts alone suffice
+        for _ in 0..encoder.seq_len() {
+            encoder.next_part();
+        }
+
+        let mut decoder = Decoder::new();
+        let mut scanned = 0;
+        while !decoder
+            .receive(&Part::decode(&encoder.next_part().encode()).unwrap())
+            .unwrap()
+        {
+            scanned += 1;
+            assert!(scanned < 200, "decoder did not converge");
+        }
+        assert_eq!(decoder.message(), Some(message.as_slice()));
+    }
+
+    #[test]
+    fn rejects_parts_of_another_message() {
+        let mut decoder = Decoder::new();
+        decoder.receive(&Encoder::new(b"first message", 4).next_part()).unwrap();
+        assert!(matches!(
+            decoder.receive(&Encoder::new(b"other message!", 4).next_part()),
+            Err(Error::MismatchedPart)
+        ));
+    }
+
+    #[test]
+    fn rejects_inconsistent_sequence_lengths() {
+        let part = Encoder::new(b"a short message", 4).next_part();
+
+        for seq_len in [MAX_SEQ_LEN + 1, part.seq_len + 1] {
+            let forged = Part { seq_len, ..part.clone() };
+            assert!(matches!(Part::decode(&forged.encode()), Err(Error::Malformed(_))));
+        }
+    }
+
+    #[test]
+    fn caps_the_number_of_fragments() {
+        let message = vec![7u8; MAX_SEQ_LEN as usize * 3];
+        let mut encoder = Encoder::new(&message, 1);
+        assert_eq!(encoder.seq_len(), MAX_SEQ_LEN);
+        assert!(Part::decode(&encoder.next_part().encode()).is_ok());
+    }
+
+    #[test]
+    fn starts_over_after_a_checksum_mismatch() {
+        let message = b"corrupted in transit";
+        let mut encoder = Encoder::new(message, 4);
+        let parts: Vec<Part> = (0..encoder.seq_len()).map(|_| encoder.next_part()).collect();
+
+        let mut decoder = Decoder::new();
+        let (last, rest) = parts.split_last().unwrap();
+        for part in rest {
+            decoder.receive(part).unwrap();
+        }
+        let mut corrupt = last.clone();
+        corrupt.data[0] ^= 1;
+        assert!(matches!(decoder.receive(&corrupt), Err(Error::ChecksumMismatch)));
+        assert_eq!(decoder.progress(), 0.0);
+
+        for part in &parts {
+            decoder.receive(part).unwrap();
+        }
+        assert_eq!(decoder.message(), Some(message.as_slice()));
+    }
+}
@@ -0,0 +1,223 @@
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use penumbra_keys::keys::{SpendKey, SpendKeyBytes};
+use zeroize::Zeroizing;
+
+use super::{decrypt, encrypt, sign_with_spend_key, Error, KdfParams, KeyStore};
+use crate::ProofError;
+
+/// File extension of keystores written by [`FileKeyStore`].
+const EXTENSION: &str = "keystore";
+
+/// A key store keeping one password-encrypted keystore file per id in a directory.
+pub struct FileKeyStore {
+    directory: PathBuf,
+    password: Zeroizing<Vec<u8>>,
+    params: KdfParams,
+    overwrite: bool,
+}
+
+impl FileKeyStore {
+    pub fn new(directory: impl Into<PathBuf>, password: &[u8]) -> Result<Self, Error> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).map_err(|e| Error::Io(e.to_string()))?;
+
+        Ok(Self {
+            directory,
+            password: Zeroizing::new(password.to_vec()),
+            params: KdfParams::default(),
+            overwrite: false,
+        })
+    }
+
+    /// Lets [`KeyStore::store`] replace a key already stored under the same
+    /// id. Off by default, so storing never silently destroys a key.
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Ids end up in file names, so keep them to a safe alphabet.
+    fn path(&self, id: &str) -> Result<PathBuf, Error> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::InvalidId(id.to_string()));
+        }
+
+        Ok(self.directory.join(format!("{id}.{EXTENSION}")))
+    }
+
+    /// Decrypts the keystore stored under `id`.
+    fn spend_key(&self, id: String) -> Result<SpendKey, Error> {
+        let keystore = fs::read(self.path(&id)?).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::NotFound(id.clone()),
+            _ => Error::Io(e.to_string()),
+        })?;
+
+        decrypt(&keystore, &self.password)
+    }
+}
+
+impl KeyStore for FileKeyStore {
+    fn store(&self, id: String, spend_key: Vec<u8>) -> Result<(), ProofError> {
+        let spend_key = Zeroizing::new(spend_key);
+        let spend_key_bytes: Zeroizing<[u8; 32]> =
+            Zeroizing::new(spend_key.as_slice().try_into().map_err(|_| Error::Malformed)?);
+        let keystore = encrypt(&SpendKey::from(SpendKeyBytes(*spend_key_bytes)), &self.password, self.params)?;
+
+        let path = self.path(&id)?;
+
+        // Write a private temp file first, so a crash never leaves a torn keystore
+        let tmp = path.with_extension("tmp");
+        write_private(&tmp, &keystore)?;
+
+        // A hard link fails if the id exists, a rename replaces it; both are atomic
+        let installed = if self.overwrite {
+            fs::rename(&tmp, &path)
+        } else {
+            fs::hard_link(&tmp, &path).and_then(|()| fs::remove_file(&tmp))
+        };
+        installed.map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            match e.kind() {
+                ErrorKind::AlreadyExists => Error::AlreadyExists(id),
+                _ => Error::Io(e.to_string()),
+            }
+        })?;
+
+        Ok(())
+    }
+
+    fn load(&self, id: String) -> Result<Vec<u8>, ProofError> {
+        Ok(self.spend_key(id)?.to_bytes().0.to_vec())
+    }
+
+    fn delete(&self, id: String) -> Result<(), ProofError> {
+        Ok(fs::remove_file(self.path(&id)?).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::NotFound(id.clone()),
+            _ => Error::Io(e.to_string()),
+        })?)
+    }
+
+    fn list(&self) -> Result<Vec<String>, ProofError> {
+        let mut ids = Vec::new();
+        let entries = fs::read_dir(&self.directory).map_err(|e| Error::Io(e.to_string()))?;
+        for entry in entries {
+            let path = entry.map_err(|e| Error::Io(e.to_string()))?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+                continue;
+            }
+            if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+                ids.push(id.to_string());
+            }
+        }
+        ids.sort();
+
+        Ok(ids)
+    }
+
+    fn sign(&self, id: String, randomizer: Vec<u8>, message: Vec<u8>) -> Result<Vec<u8>, ProofError> {
+        sign_with_spend_key(&self.spend_key(id)?, &randomizer, &message)
+    }
+
+    fn full_viewing_key(&self, id: String) -> Result<String, ProofError> {
+        Ok(self.spend_key(id)?.full_viewing_key().to_string())
+    }
+}
+
+/// Creates `path`, readable and writable by the owner only, holding `bytes`.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), Error> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).map_err(|e| Error::Io(e.to_string()))?;
+    file.write_all(bytes)
+        .and_then(|()| file.sync_all())
+        .map_err(|e| {
+            let _ = fs::remove_file(path);
+            Error::Io(e.to_string())
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ff::UniformRand;
+    use decaf377::Fr;
+    use decaf377_rdsa::{Signature, SpendAuth};
+    use penumbra_keys::keys::{Bip44Path, SeedPhrase};
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn file_key_store_roundtrip() {
+        let directory = std::env::temp_dir().join(format!("pocketlib-keystore-{}", std::process::id()));
+        let store = FileKeyStore::new(&directory, b"correct horse").unwrap();
+        let spend_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(OsRng), &Bip44Path::new(0));
+
+        let bytes = spend_key.to_bytes().0.to_vec();
+
+        store.store("alice".to_string(), bytes.clone()).unwrap();
+        assert_eq!(store.list().unwrap(), vec!["alice".to_string()]);
+        assert_eq!(store.load("alice".to_string()).unwrap(), bytes);
+        assert_eq!(
+            store.full_viewing_key("alice".to_string()).unwrap(),
+            spend_key.full_viewing_key().to_string()
+        );
+
+        // Signs under the randomized key without handing the spend key out
+        let randomizer = Fr::rand(&mut OsRng);
+        let signature = store
+            .sign("alice".to_string(), randomizer.to_bytes().to_vec(), b"message".to_vec())
+            .unwrap();
+        let rk = spend_key.full_viewing_key().spend_verification_key().randomize(&randomizer);
+        assert!(rk
+            .verify(b"message", &Signature::<SpendAuth>::try_from(signature.as_slice()).unwrap())
+            .is_ok());
+        assert!(matches!(
+            store.store("../alice".to_string(), bytes),
+            Err(ProofError::InvalidKeystore(_))
+        ));
+
+        store.delete("alice".to_string()).unwrap();
+        assert!(matches!(store.load("alice".to_string()), Err(ProofError::KeyNotFound(_))));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn store_replaces_a_key_only_when_asked() {
+        let directory = std::env::temp_dir().join(format!("pocketlib-overwrite-{}", std::process::id()));
+        let store = FileKeyStore::new(&directory, b"correct horse").unwrap();
+        let first = SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(OsRng), &Bip44Path::new(0));
+        let second = SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(OsRng), &Bip44Path::new(0));
+
+        store.store("alice".to_string(), first.to_bytes().0.to_vec()).unwrap();
+        assert!(matches!(
+            store.store("alice".to_string(), second.to_bytes().0.to_vec()),
+            Err(ProofError::KeyExists(_))
+        ));
+        assert_eq!(store.load("alice".to_string()).unwrap(), first.to_bytes().0.to_vec());
+        assert_eq!(store.list().unwrap(), vec!["alice".to_string()]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(store.path("alice").unwrap()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let store = store.overwrite(true);
+        store.store("alice".to_string(), second.to_bytes().0.to_vec()).unwrap();
+        assert_eq!(store.load("alice".to_string()).unwrap(), second.to_bytes().0.to_vec());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
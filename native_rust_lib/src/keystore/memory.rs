@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use zeroize::Zeroizing;
+
+use super::{sign_with_spend_key, KeyStore};
+use crate::ProofError;
+
+/// A key store that lives only as long as the process, for tests.
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    keys: Mutex<BTreeMap<String, Zeroizing<Vec<u8>>>>,
+}
+
+impl MemoryKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyStore for MemoryKeyStore {
+    fn store(&self, id: String, spend_key: Vec<u8>) -> Result<(), ProofError> {
+        self.keys.lock().unwrap().insert(id, Zeroizing::new(spend_key));
+        Ok(())
+    }
+
+    fn load(&self, id: String) -> Result<Vec<u8>, ProofError> {
+        let keys = self.keys.lock().unwrap();
+        let bytes = keys.get(&id).ok_or(ProofError::KeyNotFound(id))?;
+        Ok(bytes.to_vec())
+    }
+
+    fn delete(&self, id: String) -> Result<(), ProofError> {
+        self.keys
+            .lock()
+            .unwrap()
+            .remove(&id)
+            .map(|_| ())
+            .ok_or(ProofError::KeyNotFound(id))
+    }
+
+    fn list(&self) -> Result<Vec<String>, ProofError> {
+        Ok(self.keys.lock().unwrap().keys().cloned().collect())
+    }
+
+    fn sign(&self, id: String, randomizer: Vec<u8>, message: Vec<u8>) -> Result<Vec<u8>, ProofError> {
+        sign_with_spend_key(&crate::spend_key_from_bytes(self.load(id)?)?, &randomizer, &message)
+    }
+
+    fn full_viewing_key(&self, id: String) -> Result<String, ProofError> {
+        Ok(crate::spend_key_from_bytes(self.load(id)?)?.full_viewing_key().to_string())
+    }
+}
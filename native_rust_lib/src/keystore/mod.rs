@@ -0,0 +1,234 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use penumbra_keys::keys::{SpendKey, SpendKeyBytes};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+use crate::secret::{sign_randomized, Secret};
+use crate::ProofError;
+
+pub mod file;
+pub mod memory;
+
+pub use file::FileKeyStore;
+pub use memory::MemoryKeyStore;
+
+/// Magic bytes opening every keystore.
+pub const MAGIC: &[u8; 4] = b"PLKS";
+/// The only keystore version this library writes.
+pub const VERSION: u8 = 1;
+
+const SALT_LEN_BYTES: usize = 16;
+const NONCE_LEN_BYTES: usize = 12;
+/// magic || version || m_cost || t_cost || p_cost || salt || nonce
+const HEADER_LEN_BYTES: usize = 4 + 1 + 3 * 4 + SALT_LEN_BYTES + NONCE_LEN_BYTES;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("keystore is malformed")]
+    Malformed,
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u8),
+    #[error("keystore KDF parameters exceed the supported limits")]
+    KdfParamsTooCostly,
+    #[error("wrong password or corrupted keystore")]
+    WrongPassword,
+    #[error("no key stored under {0}")]
+    NotFound(String),
+    #[error("a key is already stored under {0}")]
+    AlreadyExists(String),
+    #[error("invalid key id {0}")]
+    InvalidId(String),
+    #[error("keystore I/O failed: {0}")]
+    Io(String),
+}
+
+/// Storage for raw spend key bytes, addressed by an application-chosen id.
+///
+/// Exported to the foreign side, so an app can back it with the platform
+/// keychain. The key is loaded once per operation and only used in Rust.
+#[uniffi::export(with_foreign)]
+pub trait KeyStore: Send + Sync {
+    fn store(&self, id: String, spend_key: Vec<u8>) -> Result<(), ProofError>;
+
+    /// Fails with [`ProofError::KeyNotFound`] when nothing is stored under `id`.
+    fn load(&self, id: String) -> Result<Vec<u8>, ProofError>;
+
+    fn delete(&self, id: String) -> Result<(), ProofError>;
+
+    fn list(&self) -> Result<Vec<String>, ProofError>;
+
+    /// Signs `message` with the spend authorization key stored under `id`,
+    /// randomized by the 32-byte `randomizer`, and returns the 64-byte
+    /// signature. A store that can sign never has to hand the key out.
+    fn sign(&self, id: String, randomizer: Vec<u8>, message: Vec<u8>) -> Result<Vec<u8>, ProofError>;
+
+    /// The encoded full viewing key of the spend key stored under `id`.
+    fn full_viewing_key(&self, id: String) -> Result<String, ProofError>;
+}
+
+/// [`KeyStore::sign`] for stores that hold the spend key itself.
+fn sign_with_spend_key(spend_key: &SpendKey, randomizer: &[u8], message: &[u8]) -> Result<Vec<u8>, ProofError> {
+    let randomizer = Secret(crate::randomizer_from_bytes(randomizer)?);
+
+    Ok(<[u8; 64]>::from(sign_randomized(spend_key, &randomizer, message)).to_vec())
+}
+
+/// Argon2id cost parameters, stored in the keystore header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub m_cost: u32,
+    /// Number of passes.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl KdfParams {
+    /// The most a keystore may ask for. The header is only authenticated by
+    /// the key it derives, so it is checked against these limits first.
+    pub const MAX: KdfParams = KdfParams {
+        m_cost: 256 * 1024,
+        t_cost: 16,
+        p_cost: 4,
+    };
+
+    fn check(&self) -> Result<(), Error> {
+        if self.m_cost > Self::MAX.m_cost || self.t_cost > Self::MAX.t_cost || self.p_cost > Self::MAX.p_cost {
+            return Err(Error::KdfParamsTooCostly);
+        }
+        Ok(())
+    }
+}
+
+impl Default for KdfParams {
+    /// The OWASP recommendation for Argon2id, light enough for a phone.
+    fn default() -> Self {
+        Self {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+/// Encrypts a spend key under `password`.
+///
+/// The key is derived with Argon2id and the spend key sealed with
+/// ChaCha20-Poly1305. The whole header is authenticated, so tampering with
+/// the KDF parameters is caught like any other corruption.
+pub fn encrypt(spend_key: &SpendKey, password: &[u8], params: KdfParams) -> Result<Vec<u8>, Error> {
+    params.check()?;
+
+    let mut salt = [0u8; SALT_LEN_BYTES];
+    let mut nonce = [0u8; NONCE_LEN_BYTES];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut keystore = Vec::with_capacity(HEADER_LEN_BYTES + 48);
+    keystore.extend_from_slice(MAGIC);
+    keystore.push(VERSION);
+    keystore.extend_from_slice(&params.m_cost.to_le_bytes());
+    keystore.extend_from_slice(&params.t_cost.to_le_bytes());
+    keystore.extend_from_slice(&params.p_cost.to_le_bytes());
+    keystore.extend_from_slice(&salt);
+    keystore.extend_from_slice(&nonce);
+
+    let cipher = cipher(password, &salt, params)?;
+    let spend_key_bytes = Zeroizing::new(spend_key.to_bytes().0);
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: spend_key_bytes.as_slice(),
+                aad: &keystore,
+            },
+        )
+        .map_err(|_| Error::Malformed)?;
+
+    keystore.extend(ciphertext);
+    Ok(keystore)
+}
+
+/// Decrypts a keystore produced by [`encrypt`].
+pub fn decrypt(keystore: &[u8], password: &[u8]) -> Result<SpendKey, Error> {
+    if keystore.len() < HEADER_LEN_BYTES || &keystore[..4] != MAGIC {
+        return Err(Error::Malformed);
+    }
+    if keystore[4] != VERSION {
+        return Err(Error::UnsupportedVersion(keystore[4]));
+    }
+
+    let (header, ciphertext) = keystore.split_at(HEADER_LEN_BYTES);
+    let read_u32 = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().expect("4 bytes"));
+    let params = KdfParams {
+        m_cost: read_u32(5),
+        t_cost: read_u32(9),
+        p_cost: read_u32(13),
+    };
+    params.check()?;
+    let salt = &header[17..17 + SALT_LEN_BYTES];
+    let nonce = &header[17 + SALT_LEN_BYTES..];
+
+    let cipher = cipher(password, salt, params)?;
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| Error::WrongPassword)?,
+    );
+
+    let spend_key_bytes: Zeroizing<[u8; 32]> =
+        Zeroizing::new(plaintext.as_slice().try_into().map_err(|_| Error::Malformed)?);
+    Ok(SpendKey::from(SpendKeyBytes(*spend_key_bytes)))
+}
+
+fn cipher(password: &[u8], salt: &[u8], params: KdfParams) -> Result<ChaCha20Poly1305, Error> {
+    let params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+        .map_err(|_| Error::Malformed)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password, salt, key.as_mut())
+        .map_err(|_| Error::Malformed)?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_slice())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use penumbra_keys::keys::{Bip44Path, SeedPhrase};
+
+    #[test]
+    fn keystore_roundtrip() {
+        let spend_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(OsRng), &Bip44Path::new(0));
+        let keystore = encrypt(&spend_key, b"correct horse", KdfParams::default()).unwrap();
+
+        let unlocked = decrypt(&keystore, b"correct horse").unwrap();
+        assert_eq!(unlocked.to_bytes().0, spend_key.to_bytes().0);
+        assert!(matches!(decrypt(&keystore, b"battery staple"), Err(Error::WrongPassword)));
+
+        // The header is authenticated
+        let mut tampered = keystore.clone();
+        tampered[9] ^= 1;
+        assert!(decrypt(&tampered, b"correct horse").is_err());
+
+        // Costly KDF parameters are refused before any key derivation
+        let mut costly = keystore.clone();
+        costly[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(decrypt(&costly, b"correct horse"), Err(Error::KdfParamsTooCostly)));
+
+        let mut future = keystore;
+        future[4] = VERSION + 1;
+        assert!(matches!(decrypt(&future, b"correct horse"), Err(Error::UnsupportedVersion(_))));
+    }
+}
@@ -4,7 +4,7 @@ use jni::JNIEnv;
 use jni::objects::{JClass, JString, JObject, JValue, JByteArray};
 use jni::sys::{jlong, jint, jboolean, jobject};
 use once_cell::sync::Lazy;
-use penumbra_keys::keys::{Diversifier, SpendKeyBytes};
+use penumbra_keys::keys::{AddressIndex, Diversifier, SpendKeyBytes};
 use std::sync::{Arc, Mutex};
 
 
@@ -25,7 +25,7 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_generat
         .expect("Couldn't get java string!")
         .into();
 
-    match PROOF_MANAGER.lock().unwrap().generate_keys(seed_phrase) {
+    match PROOF_MANAGER.lock().unwrap().generate_keys(seed_phrase, 0) {
         Ok(key_pair) => {
             let hash_map_class = env.find_class("java/util/HashMap")
                 .expect("Failed to find HashMap class");
@@ -47,7 +47,9 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_generat
                 ).expect("Failed to call put");
             };
 
-            put_bytes("spendKey", &key_pair.spend_key);
+            // Java owns its copy of the spend key now; wipe ours
+            let spend_key = Zeroizing::new(key_pair.spend_key);
+            put_bytes("spendKey", &spend_key);
             put_bytes("viewKey", &key_pair.view_key);
 
             hash_map.into_raw()
@@ -192,6 +194,9 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_createN
 
             // Add note data to HashMap
             put_bytes("commitment", &note.commitment);
+            put_bytes("amount", &note.amount.to_le_bytes());
+            put_bytes("assetId", &note.asset_id.to_le_bytes());
+            put_bytes("rseed", &note.rseed);
             
             // Add addresses
             let mut put_address = |prefix: &str, addr: &AddressData| {
@@ -218,55 +223,162 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_createN
 }
 
 #[no_mangle]
-pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_signNoteNative<'local>(
+pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_createProofNative<'local>(
     mut env: JNIEnv<'local>,
     _class: JClass<'local>,
-    seed_phrase: JString<'local>,
-    note_obj: JObject<'local>,
+    debtor_address: JObject<'local>,
+    creditor_address: JObject<'local>,
+    amount: jlong,
+    asset_id: jlong,
 ) -> jobject {
-    let seed_phrase: String = env
-        .get_string(&seed_phrase)
-        .expect("Couldn't get java string!")
-        .into();
-
-    // Helper function to get note from Java HashMap
-    let mut get_note = |note_obj: JObject| -> Result<Note, jni::errors::Error> {
+    // Helper function to get address data from Java HashMap
+    let mut get_address_data = |addr_obj: JObject| -> Result<AddressData, jni::errors::Error> {
         let mut get_bytes = |key: &str| -> Result<Vec<u8>, jni::errors::Error> {
             let j_key = env.new_string(key)?;
             let bytes = env.call_method(
-                &note_obj,
+                &addr_obj,
                 "get",
                 "(Ljava/lang/Object;)Ljava/lang/Object;",
                 &[JValue::Object(&j_key.into())]
             )?.l()?;
+            
     
             // Convert to JByteArray first
             let byte_array = JByteArray::from(bytes);
             env.convert_byte_array(&byte_array)
         };
 
-        let mut get_address = |prefix: &str| -> Result<AddressData, jni::errors::Error> {
-            Ok(AddressData {
-                diversifier: get_bytes(&format!("{}Diversifier", prefix))?,
-                transmission_key: get_bytes(&format!("{}TransmissionKey", prefix))?,
-                clue_key: get_bytes(&format!("{}ClueKey", prefix))?,
-            })
-        };
+        Ok(AddressData {
+            diversifier: get_bytes("diversifier")?,
+            transmission_key: get_bytes("transmissionKey")?,
+            clue_key: get_bytes("clueKey")?,
+        })
+    };
 
-        Ok(Note {
-            debtor_address: get_address("debtor")?,
-            creditor_address: get_address("creditor")?,
-            amount: 0, // These values are not needed for signing
-            asset_id: 0,
-            commitment: get_bytes("commitment")?,
+    let result = (|| -> Result<ProvenNote, ProofError> {
+        let debtor = get_address_data(debtor_address)
+            .map_err(|_| ProofError::InvalidKey)?;
+        let creditor = get_address_data(creditor_address)
+            .map_err(|_| ProofError::InvalidKey)?;
+
+        PROOF_MANAGER.lock().unwrap().prove_note_opening(
+            debtor,
+            creditor,
+            amount as u64,
+            asset_id as u64,
+        )
+    })();
+
+    match result {
+        Ok(ProvenNote { note, proof }) => {
+            let hash_map_class = env.find_class("java/util/HashMap")
+                .expect("Failed to find HashMap class");
+            let hash_map = env.new_object(hash_map_class, "()V", &[])
+                .expect("Failed to create HashMap");
+
+            let mut put_bytes = |key: &str, bytes: &[u8]| {
+                let j_key = env.new_string(key)
+                    .expect("Failed to create string");
+                let j_value = env.byte_array_from_slice(bytes)
+                    .expect("Failed to create byte array");
+
+                env.call_method(
+                    &hash_map,
+                    "put",
+                    "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+                    &[JValue::Object(&j_key.into()), JValue::Object(&j_value.into())]
+                ).expect("Failed to call put");
+            };
+
+            // The proof, then the note opening it proves
+            put_bytes("proof", &proof);
+            put_bytes("commitment", &note.commitment);
+            put_bytes("amount", &note.amount.to_le_bytes());
+            put_bytes("assetId", &note.asset_id.to_le_bytes());
+            put_bytes("rseed", &note.rseed);
+            
+            // Add addresses
+            let mut put_address = |prefix: &str, addr: &AddressData| {
+                put_bytes(&format!("{}Diversifier", prefix), &addr.diversifier);
+                put_bytes(&format!("{}TransmissionKey", prefix), &addr.transmission_key);
+                put_bytes(&format!("{}ClueKey", prefix), &addr.clue_key);
+            };
+
+            put_address("debtor", &note.debtor_address);
+            put_address("creditor", &note.creditor_address);
+
+            hash_map.into_raw()
+        },
+        Err(e) => {
+            env.throw_new("java/lang/Exception", e.to_string())
+                .expect("Failed to throw exception");
+            let hash_map_class = env.find_class("java/util/HashMap")
+                .expect("Failed to find HashMap class");
+            env.new_object(hash_map_class, "()V", &[])
+                .expect("Failed to create empty HashMap")
+                .into_raw()
+        }
+    }
+}
+
+// Reads a note from a Java HashMap laid out as createNoteNative returns it
+fn get_note(env: &mut JNIEnv, note_obj: &JObject) -> Result<Note, jni::errors::Error> {
+    let mut get_bytes = |key: &str| -> Result<Vec<u8>, jni::errors::Error> {
+        let j_key = env.new_string(key)?;
+        let bytes = env.call_method(
+            note_obj,
+            "get",
+            "(Ljava/lang/Object;)Ljava/lang/Object;",
+            &[JValue::Object(&j_key.into())]
+        )?.l()?;
+
+        // Convert to JByteArray first
+        let byte_array = JByteArray::from(bytes);
+        env.convert_byte_array(&byte_array)
+    };
+
+    let mut get_address = |prefix: &str| -> Result<AddressData, jni::errors::Error> {
+        Ok(AddressData {
+            diversifier: get_bytes(&format!("{}Diversifier", prefix))?,
+            transmission_key: get_bytes(&format!("{}TransmissionKey", prefix))?,
+            clue_key: get_bytes(&format!("{}ClueKey", prefix))?,
         })
     };
 
+    // Amounts travel as little-endian u64 bytes, like everything else in the map
+    let to_u64 = |bytes: Vec<u8>| -> Result<u64, jni::errors::Error> {
+        let bytes: [u8; 8] = bytes.try_into()
+            .map_err(|_| jni::errors::Error::JavaException)?;
+        Ok(u64::from_le_bytes(bytes))
+    };
+
+    Ok(Note {
+        debtor_address: get_address("debtor")?,
+        creditor_address: get_address("creditor")?,
+        amount: to_u64(get_bytes("amount")?)?,
+        asset_id: to_u64(get_bytes("assetId")?)?,
+        commitment: get_bytes("commitment")?,
+        rseed: get_bytes("rseed")?,
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_signNoteNative<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    seed_phrase: JString<'local>,
+    note_obj: JObject<'local>,
+) -> jobject {
+    let seed_phrase: String = env
+        .get_string(&seed_phrase)
+        .expect("Couldn't get java string!")
+        .into();
+
     let result = (|| -> Result<SignedNote, ProofError> {
-        let note = get_note(note_obj)
+        let note = get_note(&mut env, &note_obj)
             .map_err(|_| ProofError::InvalidKey)?;
 
-        PROOF_MANAGER.lock().unwrap().sign_note(seed_phrase, note)
+        PROOF_MANAGER.lock().unwrap().sign_note(seed_phrase, 0, note)
     })();
 
     match result {
@@ -292,6 +404,7 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_signNot
 
             put_bytes("signature", &signed_note.signature);
             put_bytes("verificationKey", &signed_note.verification_key);
+            put_bytes("authorizationProof", &signed_note.authorization_proof);
 
             hash_map.into_raw()
         },
@@ -312,24 +425,46 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_verifyS
     mut env: JNIEnv<'local>,
     _class: JClass<'local>,
     verification_key: JByteArray<'local>,
-    commitment: JByteArray<'local>,
+    note_obj: JObject<'local>,
     signature: JByteArray<'local>,
 ) -> jboolean {
-    let convert_array = |array: JByteArray<'local>| -> Vec<u8> {
-        env.convert_byte_array(&array)
-            .map(|bytes| bytes.iter().map(|&b| b as u8).collect())
-            .unwrap_or_default()
-    };
+    let result = (|| -> Result<bool, ProofError> {
+        let verification_key_bytes = env.convert_byte_array(&verification_key)
+            .map_err(|_| ProofError::InvalidKey)?;
+        let signature_bytes = env.convert_byte_array(&signature)
+            .map_err(|_| ProofError::InvalidSignature)?;
+        let note = get_note(&mut env, &note_obj)
+            .map_err(|_| ProofError::InvalidKey)?;
+
+        PROOF_MANAGER.lock().unwrap().verify_signature(
+            verification_key_bytes,
+            note,
+            signature_bytes,
+        )
+    })();
+
+    match result {
+        Ok(result) => if result { 1 } else { 0 },
+        Err(e) => {
+            env.throw_new("java/lang/Exception", e.to_string())
+                .expect("Failed to throw exception");
+            0
+        }
+    }
+}
+
 
-    let verification_key_bytes = convert_array(verification_key);
-    let commitment_bytes = convert_array(commitment);
-    let signature_bytes = convert_array(signature);
+#[no_mangle]
+pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_verifyProofNative<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    proof: JByteArray<'local>,
+    commitment: JByteArray<'local>,
+) -> jboolean {
+    let proof_bytes = env.convert_byte_array(&proof).unwrap_or_default();
+    let commitment_bytes = env.convert_byte_array(&commitment).unwrap_or_default();
 
-    match PROOF_MANAGER.lock().unwrap().verify_signature(
-        verification_key_bytes,
-        commitment_bytes,
-        signature_bytes,
-    ) {
+    match PROOF_MANAGER.lock().unwrap().verify_note_opening(proof_bytes, commitment_bytes) {
         Ok(result) => if result { 1 } else { 0 },
         Err(e) => {
             env.throw_new("java/lang/Exception", e.to_string())
@@ -339,30 +474,107 @@ pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_verifyS
     }
 }
 
+// Circuits are numbered in ProofCircuit order: 0 note opening, 1 authorization, 2 intent
+fn circuit_from_jint(circuit: jint) -> Option<ProofCircuit> {
+    match circuit {
+        0 => Some(ProofCircuit::NoteOpening),
+        1 => Some(ProofCircuit::Authorization),
+        2 => Some(ProofCircuit::Intent),
+        _ => None,
+    }
+}
+
+fn load_key_native(
+    env: &mut JNIEnv,
+    circuit: jint,
+    key: JByteArray,
+    load: fn(&ProofManager, ProofCircuit, Vec<u8>) -> Result<(), ProofError>,
+) {
+    let Some(circuit) = circuit_from_jint(circuit) else {
+        env.throw_new("java/lang/Exception", format!("Unknown circuit: {}", circuit))
+            .expect("Failed to throw exception");
+        return;
+    };
+    let key_bytes = env.convert_byte_array(&key).unwrap_or_default();
+
+    if let Err(e) = load(&PROOF_MANAGER.lock().unwrap(), circuit, key_bytes) {
+        env.throw_new("java/lang/Exception", e.to_string())
+            .expect("Failed to throw exception");
+    }
+}
+
+// Must run before signing: release builds have no keys until the app loads them
+#[no_mangle]
+pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_loadProvingKeyNative<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    circuit: jint,
+    proving_key: JByteArray<'local>,
+) {
+    load_key_native(&mut env, circuit, proving_key, ProofManager::load_proving_key);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_expo_modules_proofmanager_ProofManagerModule_loadVerifyingKeyNative<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    circuit: jint,
+    verifying_key: JByteArray<'local>,
+) {
+    load_key_native(&mut env, circuit, verifying_key, ProofManager::load_verifying_key);
+}
+
 
 
 uniffi::setup_scaffolding!();
 
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ark_ff::UniformRand;
 use decaf377::{Fq, Fr};
 use decaf377_rdsa::{SpendAuth, VerificationKey, Signature};
 use penumbra_keys::{
-    keys::{Bip44Path, SeedPhrase, SpendKey},
+    keys::{Bip44Path, FullViewingKey, NullifierKey, SeedPhrase, SpendKey},
     Address,
 };
 use penumbra_asset::{asset, Value};
 use penumbra_num::Amount;
 use penumbra_shielded_pool::Rseed;
+use penumbra_tct::StateCommitment;
 use decaf377_ka as ka;
 use decaf377_fmd as fmd;
 
-use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroizing;
 
 // Our custom note implementation:
 mod note;
+// Groth16 circuits over our notes:
+mod proof;
+// Password-encrypted spend keys and the KeyStore trait:
+mod keystore;
+// Wiping of secrets that do not implement Zeroize, and randomized signing:
+mod secret;
+// Shamir backups of the seed:
+mod backup;
+// Per-contact address indexes:
+mod allocator;
+// Signed payment requests:
+mod request;
+// Multi-part QR transfer of large blobs:
+mod fountain;
+
+pub use keystore::KeyStore;
+use keystore::{FileKeyStore, MemoryKeyStore};
+
+use proof::{
+    AuthorizationProof, AuthorizationProofPrivate, AuthorizationProofPublic,
+    IntentProof, IntentProofPrivate, IntentProofPublic,
+    NoteOpeningProof, NoteOpeningProofPrivate, NoteOpeningProofPublic,
+};
 
 // Core FFI Types
-#[derive(uniffi::Record)]
+#[derive(Clone, uniffi::Record)]
 pub struct AddressData {
     pub diversifier: Vec<u8>,
     pub transmission_key: Vec<u8>,
@@ -371,17 +583,24 @@ pub struct AddressData {
 
 #[derive(uniffi::Record)]
 pub struct KeyPair {
+    // Handed over to the caller, which must wipe it; prefer a KeyStore
     pub spend_key: Vec<u8>,
+    // Full viewing key as raw ak || nk bytes
     pub view_key: Vec<u8>,
+    // Full viewing key in Penumbra's bech32m encoding
+    pub full_viewing_key: String,
 }
 
-#[derive(uniffi::Record)]
+#[derive(Clone, uniffi::Record)]
 pub struct Note {
     pub debtor_address: AddressData,
     pub creditor_address: AddressData,
     pub amount: u64,
     pub asset_id: u64,
     pub commitment: Vec<u8>,
+    // Blinding seed; with the fields above it is the full note opening.
+    // Handed over to the caller, which must wipe it once the note is stored.
+    pub rseed: Vec<u8>,
 }
 
 #[derive(uniffi::Record)]
@@ -389,6 +608,80 @@ pub struct SignedNote {
     pub note: Note,
     pub signature: Vec<u8>,
     pub verification_key: Vec<u8>,
+    // Proof that `verification_key` randomizes the debtor's spend key
+    pub authorization_proof: Vec<u8>,
+}
+
+// Returned to the signer only. The randomizer turns the spend verification key
+// into the note's verification key, so shipping it would link every note signed.
+// The caller owns spend_auth_randomizer and must wipe it.
+#[derive(uniffi::Record)]
+pub struct HolderSignedNote {
+    pub signed_note: SignedNote,
+    pub spend_auth_randomizer: Vec<u8>,
+}
+
+#[derive(uniffi::Record)]
+pub struct ProvenNote {
+    pub note: Note,
+    pub proof: Vec<u8>,
+}
+
+#[derive(uniffi::Record)]
+pub struct AddressIndexData {
+    // Penumbra's AddressIndex.account: which address of the wallet, not a BIP44 account
+    pub address_index: u32,
+    pub randomizer: Vec<u8>,
+}
+
+#[derive(uniffi::Record)]
+pub struct ScannedNote {
+    pub note: Note,
+    pub address_index: AddressIndexData,
+    // Position of the matching ciphertext in the scanned batch
+    pub position: u32,
+}
+
+// Which commitment a note's fields fail to open. The commitment is hiding, so
+// there is no telling which field was changed, only that one was. Fields that
+// do not even parse are reported by their own error instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum MismatchedCommitment {
+    // The commitment the note was checked against, e.g. the signed one
+    Expected,
+    // The commitment stored in the note record itself
+    Recorded,
+}
+
+// Circuits whose Groth16 keys the app loads from the setup ceremony output
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum ProofCircuit {
+    NoteOpening,
+    Authorization,
+    Intent,
+}
+
+#[derive(Clone, uniffi::Record)]
+pub struct PaymentRequestData {
+    pub creditor_address: AddressData,
+    pub amount: u64,
+    pub asset_id: u64,
+    pub memo: String,
+    // Unix time in seconds after which the request is void, or 0 for never
+    pub expires_at: u64,
+}
+
+#[derive(uniffi::Record)]
+pub struct IntentAction {
+    pub note_commitment: Vec<u8>,
+    pub auth_sig: Vec<u8>,
+    pub rk: Vec<u8>,
+    // Proof that the prover can open the commitment and that rk belongs to its debtor
+    pub zkp: Vec<u8>,
+    pub note_ciphertext: Vec<u8>,
+    pub aux_ciphertext: Vec<u8>,
+    // FMD clue for the creditor, empty when none was requested
+    pub clue: Vec<u8>,
 }
 
 // Core Error Type
@@ -402,193 +695,1948 @@ pub enum ProofError {
     InvalidSignature,
     #[error("Note creation failed: {0}")]
     NoteError(String),
+    #[error("Invalid commitment")]
+    InvalidCommitment,
+    #[error("Invalid proof")]
+    InvalidProof,
+    #[error("Proof generation failed: {0}")]
+    ProofGenerationFailed(String),
+    #[error("Signer does not control the debtor address")]
+    NotDebtor,
+    #[error("Note decryption failed")]
+    DecryptionFailed,
+    #[error("Clue creation failed: {0}")]
+    ClueError(String),
+    #[error("Watch-only ProofManager cannot sign")]
+    WatchOnly,
+    #[error("ProofManager has no full viewing key")]
+    MissingViewingKey,
+    #[error("No unused account found")]
+    NoUnusedAccount,
+    #[error("Seed phrase must have 12 or 24 words, got {0}")]
+    InvalidSeedLength(u32),
+    // Only the position is reported, never the word itself
+    #[error("Unknown seed phrase word at index {index}")]
+    UnknownSeedWord { index: u32 },
+    #[error("Invalid seed phrase checksum")]
+    InvalidSeedChecksum,
+    #[error("Invalid keystore: {0}")]
+    InvalidKeystore(String),
+    #[error("Wrong keystore password")]
+    WrongPassword,
+    #[error("No key stored under {0}")]
+    KeyNotFound(String),
+    #[error("A key is already stored under {0}")]
+    KeyExists(String),
+    #[error("ProofManager has no key store")]
+    MissingKeyStore,
+    #[error("Key store failed: {0}")]
+    KeyStoreFailed(String),
+    #[error("Invalid backup share: {0}")]
+    InvalidShare(String),
+    #[error("Invalid address string")]
+    InvalidAddress,
+    #[error("Invalid address book: {0}")]
+    InvalidAddressBook(String),
+    #[error("No address indexes left")]
+    AddressIndexExhausted,
+    #[error("Invalid payment request: {0}")]
+    InvalidPaymentRequest(String),
+    #[error("Payment request expired")]
+    PaymentRequestExpired,
+    #[error("Signer does not control the creditor address")]
+    NotCreditor,
+    #[error("Invalid multi-part transfer: {0}")]
+    InvalidMultipart(String),
+    #[error("Note fields do not open the {0:?} commitment")]
+    CommitmentMismatch(MismatchedCommitment),
+    #[error("Proof parameters missing: {0}")]
+    MissingParameters(String),
+    #[error("Invalid proof parameters: {0}")]
+    InvalidParameters(String),
 }
 
-#[derive(uniffi::Object)]
-pub struct ProofManager {
-    // Constant for spend auth as in test.rs
-    spend_auth_randomizer: Fr,
-}
-
-#[uniffi::export]
-impl ProofManager {
-    #[uniffi::constructor]
-    pub fn new() -> Result<Arc<Self>, ProofError> {
-        Ok(Arc::new(Self {
-            spend_auth_randomizer: Fr::from(1u64),
-        }))
-    }
-
+// Upper bound on the accounts walked by account discovery
+const MAX_DISCOVERED_ACCOUNTS: u32 = 1000;
 
-    // Key Generation
-    fn generate_keys(&self, seed_phrase: String) -> Result<KeyPair, ProofError> {
-        let seed = SeedPhrase::from_str(&seed_phrase)
-            .map_err(|_| ProofError::InvalidSeed)?;
-        
-        let spend_key = SpendKey::from_seed_phrase_bip44(seed, &Bip44Path::new(0));
-        let view_key = spend_key.full_viewing_key();
+// Implemented by the app to tell account discovery whether an account has been used
+#[uniffi::export(callback_interface)]
+pub trait AccountActivity: Send + Sync {
+    fn is_account_used(&self, bip44_account: u32, full_viewing_key: String) -> bool;
+}
 
-        Ok(KeyPair {
-            spend_key: spend_key.to_bytes().0.to_vec(),
-            view_key: view_key.nullifier_key().0.to_bytes().to_vec(), // Not sure about this
-        })
+impl AddressData {
+    fn to_address(&self) -> Result<Address, ProofError> {
+        Address::from_components(
+            Diversifier(self.diversifier.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
+            ka::Public(self.transmission_key.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
+            fmd::ClueKey(self.clue_key.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
+        ).ok_or_else(|| ProofError::InvalidKey)
     }
+}
 
-     // Address Generation
-     fn generate_address(&self, spend_key_bytes: Vec<u8>, index: u32) -> Result<AddressData, ProofError> {
-        let spend_key_bytes: [u8; 32] = spend_key_bytes.try_into()
-            .map_err(|_| ProofError::InvalidKey)?;
-        let spend_key = SpendKey::from(SpendKeyBytes(spend_key_bytes));
-        
-        let fvk = spend_key.full_viewing_key();
-        let ivk = fvk.incoming();
-        let (address, _) = ivk.payment_address(index.into());
-
-        Ok(AddressData {
+impl From<&Address> for AddressData {
+    fn from(address: &Address) -> Self {
+        AddressData {
             diversifier: address.diversifier().0.to_vec(),
             transmission_key: address.transmission_key().0.to_vec(),
             clue_key: address.clue_key().0.to_vec(),
-        })
+        }
     }
+}
 
+impl TryFrom<&note::Note> for Note {
+    type Error = ProofError;
 
-    // Create Note
-    pub fn create_note(
-        &self,
-        debtor_address: AddressData,
-        creditor_address: AddressData,
-        amount: u64,
-        asset_id: u64,
-    ) -> Result<Note, ProofError> {
-        // Clone the data before conversion
-        let debtor_addr = Address::from_components(
-        Diversifier(debtor_address.diversifier.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
-        ka::Public(debtor_address.transmission_key.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
-        fmd::ClueKey(debtor_address.clue_key.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
-    ).ok_or_else(|| ProofError::InvalidKey)?;
-
-    let creditor_addr = Address::from_components(
-        Diversifier(creditor_address.diversifier.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
-        ka::Public(creditor_address.transmission_key.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
-        fmd::ClueKey(creditor_address.clue_key.clone().try_into().map_err(|_| ProofError::InvalidKey)?),
-    ).ok_or_else(|| ProofError::InvalidKey)?;
-    
-
-        let value = Value {
-            amount: Amount::from(amount),
-            asset_id: asset::Id(Fq::from(asset_id)),
-        };
-
-        let mut rng = rand::thread_rng();
-        let mut rseed_bytes = [0u8; 32];
-        rng.fill_bytes(&mut rseed_bytes);
-
-        let note = crate::note::Note::from_parts(
-            debtor_addr,
-            creditor_addr,
-            value,
-            Rseed(rseed_bytes),
-        ).map_err(|e| ProofError::NoteError(e.to_string()))?;
+    fn try_from(note: &note::Note) -> Result<Self, Self::Error> {
+        let amount = u64::try_from(note.amount().value())
+            .map_err(|_| ProofError::NoteError("amount does not fit in a u64".to_string()))?;
 
-        let commitment = note.commit().0.to_bytes();
+        // Asset ids are created from a u64, so only the low 8 bytes may be set
+        let asset_id_bytes = note.asset_id().0.to_bytes();
+        if asset_id_bytes[8..].iter().any(|&b| b != 0) {
+            return Err(ProofError::NoteError("asset id does not fit in a u64".to_string()));
+        }
+        let asset_id = u64::from_le_bytes(asset_id_bytes[..8].try_into().expect("8 bytes"));
 
         Ok(Note {
-            debtor_address,
-            creditor_address,
+            debtor_address: AddressData::from(&note.debtor()),
+            creditor_address: AddressData::from(&note.creditor()),
             amount,
             asset_id,
-            commitment: commitment.to_vec(),
+            commitment: note.commit().0.to_bytes().to_vec(),
+            rseed: note.rseed().0.to_vec(),
         })
     }
+}
 
-    // Sign Note
-    pub fn sign_note(
-        &self,
-        seed_phrase: String,
-        note: Note,
-    ) -> Result<SignedNote, ProofError> {
-        // Generate spend key from seed phrase
-        let seed = SeedPhrase::from_str(&seed_phrase)
-            .map_err(|_| ProofError::InvalidSeed)?;
-        let spend_key = SpendKey::from_seed_phrase_bip44(seed, &Bip44Path::new(0));
+impl Note {
+    // Recompute the commitment from the opening and compare it with `commitment`
+    fn verify_commitment(&self, commitment: &[u8]) -> Result<(), ProofError> {
+        // Fields that cannot form a note fail with their own error
+        let recomputed = self.to_note()?.commit().0.to_bytes();
 
-        // Create randomized spend auth key
-        let rsk = spend_key.spend_auth_key().randomize(&self.spend_auth_randomizer);
-        let rk: VerificationKey<SpendAuth> = rsk.into();
+        if commitment != recomputed {
+            return Err(ProofError::CommitmentMismatch(MismatchedCommitment::Expected));
+        }
+        if self.commitment != recomputed {
+            return Err(ProofError::CommitmentMismatch(MismatchedCommitment::Recorded));
+        }
+        Ok(())
+    }
 
-        // Sign the commitment
-        let signature = {
-            let sig = rsk.sign(rand::thread_rng(), &note.commitment);
-            Vec::<u8>::from(sig)
-        };
+    // The full opening behind this record
+    fn to_note(&self) -> Result<note::Note, ProofError> {
+        let rseed: [u8; 32] = self.rseed.as_slice().try_into()
+            .map_err(|_| ProofError::NoteError("rseed must be 32 bytes".to_string()))?;
 
-        Ok(SignedNote {
-            note,
-            signature,
-            verification_key: rk.to_bytes().to_vec(),
-        })
+        note_from_parts(&self.debtor_address, &self.creditor_address, self.amount, self.asset_id, Rseed(rseed))
     }
+}
 
-       // Verify signature
-       pub fn verify_signature(
-        &self,
-        verification_key_bytes: Vec<u8>,
-        commitment: Vec<u8>,
-        signature: Vec<u8>,
-    ) -> Result<bool, ProofError> {
-        let rk = VerificationKey::<SpendAuth>::try_from(verification_key_bytes.as_slice())
-            .map_err(|_| ProofError::InvalidKey)?;
-
-        let sig = Signature::try_from(signature.as_slice())
-            .map_err(|_| ProofError::InvalidSignature)?;
+impl From<keystore::Error> for ProofError {
+    fn from(e: keystore::Error) -> Self {
+        match e {
+            keystore::Error::WrongPassword => ProofError::WrongPassword,
+            keystore::Error::NotFound(id) => ProofError::KeyNotFound(id),
+            keystore::Error::AlreadyExists(id) => ProofError::KeyExists(id),
+            e => ProofError::InvalidKeystore(e.to_string()),
+        }
+    }
+}
 
-        // Map the verification result to a bool
-        Ok(rk.verify(&commitment, &sig).is_ok())
+// Raised when a foreign KeyStore implementation throws something unexpected
+impl From<uniffi::UnexpectedUniFFICallbackError> for ProofError {
+    fn from(e: uniffi::UnexpectedUniFFICallbackError) -> Self {
+        ProofError::KeyStoreFailed(e.reason)
     }
+}
 
+impl From<backup::Error> for ProofError {
+    fn from(e: backup::Error) -> Self {
+        ProofError::InvalidShare(e.to_string())
+    }
 }
 
-// // Tests
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
+impl From<request::Error> for ProofError {
+    fn from(e: request::Error) -> Self {
+        match e {
+            request::Error::Expired => ProofError::PaymentRequestExpired,
+            request::Error::InvalidSignature => ProofError::InvalidSignature,
+            request::Error::NotCreditor => ProofError::NotCreditor,
+            request::Error::ProofGenerationFailed(e) => ProofError::ProofGenerationFailed(e),
+            request::Error::Parameters(e) => e.into(),
+            e => ProofError::InvalidPaymentRequest(e.to_string()),
+        }
+    }
+}
 
-//     #[test]
-//     fn test_full_flow() -> Result<(), CryptoError> {
-//         let manager = CryptoManager::new()?;
+impl PaymentRequestData {
+    fn to_request(&self) -> Result<request::PaymentRequest, ProofError> {
+        Ok(request::PaymentRequest {
+            creditor: self.creditor_address.to_address()?,
+            amount: self.amount,
+            asset_id: self.asset_id,
+            memo: self.memo.clone(),
+            expires_at: self.expires_at,
+        })
+    }
+}
 
-//         // Generate keys
-//         let keys = manager.generate_keys(
-//             "test test test test test test test test test test test junk".to_string()
-//         )?;
+impl From<&request::PaymentRequest> for PaymentRequestData {
+    fn from(request: &request::PaymentRequest) -> Self {
+        PaymentRequestData {
+            creditor_address: AddressData::from(&request.creditor),
+            amount: request.amount,
+            asset_id: request.asset_id,
+            memo: request.memo.clone(),
+            expires_at: request.expires_at,
+        }
+    }
+}
+
+impl From<ProofCircuit> for proof::Circuit {
+    fn from(circuit: ProofCircuit) -> Self {
+        match circuit {
+            ProofCircuit::NoteOpening => proof::Circuit::NoteOpening,
+            ProofCircuit::Authorization => proof::Circuit::Authorization,
+            ProofCircuit::Intent => proof::Circuit::Intent,
+        }
+    }
+}
+
+impl From<proof::ParameterError> for ProofError {
+    fn from(e: proof::ParameterError) -> Self {
+        match e {
+            proof::ParameterError::Invalid(..) => ProofError::InvalidParameters(e.to_string()),
+            e => ProofError::MissingParameters(e.to_string()),
+        }
+    }
+}
+
+// A failed proof is reported as such, unless it failed for want of parameters
+fn proof_generation_error(e: anyhow::Error) -> ProofError {
+    match e.downcast::<proof::ParameterError>() {
+        Ok(e) => e.into(),
+        Err(e) => ProofError::ProofGenerationFailed(e.to_string()),
+    }
+}
+
+// A proof that does not verify is Ok(false); missing parameters are an error
+fn proof_verified(result: anyhow::Result<()>) -> Result<bool, ProofError> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(e) => match e.downcast::<proof::ParameterError>() {
+            Ok(e) => Err(e.into()),
+            Err(_) => Ok(false),
+        },
+    }
+}
+
+impl From<fountain::Error> for ProofError {
+    fn from(e: fountain::Error) -> Self {
+        ProofError::InvalidMultipart(e.to_string())
+    }
+}
+
+impl From<AddressIndex> for AddressIndexData {
+    fn from(index: AddressIndex) -> Self {
+        AddressIndexData {
+            address_index: index.account,
+            randomizer: index.randomizer.to_vec(),
+        }
+    }
+}
+
+fn full_viewing_key_to_bytes(fvk: &FullViewingKey) -> Vec<u8> {
+    let mut bytes = fvk.spend_verification_key().to_bytes().to_vec();
+    bytes.extend_from_slice(&fvk.nullifier_key().0.to_bytes());
+    bytes
+}
+
+fn full_viewing_key_from_bytes(bytes: &[u8]) -> Result<FullViewingKey, ProofError> {
+    if bytes.len() != 64 {
+        return Err(ProofError::InvalidKey);
+    }
+    let ak = VerificationKey::<SpendAuth>::try_from(&bytes[..32])
+        .map_err(|_| ProofError::InvalidKey)?;
+    let nk: [u8; 32] = bytes[32..].try_into().expect("32 bytes");
+    let nk = Fq::from_bytes_checked(&nk)
+        .map_err(|_| ProofError::InvalidKey)?;
+
+    Ok(FullViewingKey::from_components(ak, NullifierKey(nk)))
+}
+
+// Seed phrases are taken by value so they are wiped once parsed
+fn parse_mnemonic(seed_phrase: String) -> Result<bip39::Mnemonic, ProofError> {
+    let seed_phrase = Zeroizing::new(seed_phrase);
+    let words: Vec<&str> = seed_phrase.split_whitespace().collect();
+    if words.len() != 12 && words.len() != 24 {
+        return Err(ProofError::InvalidSeedLength(words.len() as u32));
+    }
+
+    // Penumbra uses the BIP39 English wordlist, so BIP39 tells us what is wrong
+    let normalized = Zeroizing::new(words.join(" "));
+    bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &normalized)
+        .map_err(|e| match e {
+            bip39::Error::UnknownWord(i) => ProofError::UnknownSeedWord { index: i as u32 },
+            bip39::Error::InvalidChecksum => ProofError::InvalidSeedChecksum,
+            _ => ProofError::InvalidSeed,
+        })
+}
+
+fn parse_seed_phrase(seed_phrase: String) -> Result<SeedPhrase, ProofError> {
+    let normalized = Zeroizing::new(parse_mnemonic(seed_phrase)?.to_string());
+
+    SeedPhrase::from_str(&normalized)
+        .map_err(|_| ProofError::InvalidSeed)
+}
+
+fn spend_key_from_seed(seed_phrase: String, bip44_account: u32) -> Result<SpendKey, ProofError> {
+    let seed = parse_seed_phrase(seed_phrase)?;
+    Ok(SpendKey::from_seed_phrase_bip44(seed, &Bip44Path::new(bip44_account)))
+}
+
+fn spend_key_from_bytes(spend_key_bytes: Vec<u8>) -> Result<SpendKey, ProofError> {
+    let spend_key_bytes = Zeroizing::new(spend_key_bytes);
+    let spend_key_bytes: Zeroizing<[u8; 32]> = Zeroizing::new(
+        spend_key_bytes.as_slice().try_into()
+            .map_err(|_| ProofError::InvalidKey)?,
+    );
+    Ok(SpendKey::from(SpendKeyBytes(*spend_key_bytes)))
+}
+
+fn randomizer_from_bytes(randomizer: &[u8]) -> Result<Fr, ProofError> {
+    let randomizer: [u8; 32] = randomizer.try_into()
+        .map_err(|_| ProofError::InvalidKey)?;
+    Fr::from_bytes_checked(&randomizer)
+        .map_err(|_| ProofError::InvalidKey)
+}
+
+fn note_from_parts(
+    debtor_address: &AddressData,
+    creditor_address: &AddressData,
+    amount: u64,
+    asset_id: u64,
+    rseed: Rseed,
+) -> Result<note::Note, ProofError> {
+    let debtor_addr = debtor_address.to_address()?;
+    let creditor_addr = creditor_address.to_address()?;
+
+    note::Note::from_parts(
+        debtor_addr,
+        creditor_addr,
+        value_from_parts(amount, asset_id),
+        rseed,
+    ).map_err(|e| ProofError::NoteError(e.to_string()))
+}
+
+fn value_from_parts(amount: u64, asset_id: u64) -> Value {
+    Value {
+        amount: Amount::from(amount),
+        asset_id: asset::Id(Fq::from(asset_id)),
+    }
+}
+
+fn commitment_from_bytes(commitment: &[u8]) -> Result<StateCommitment, ProofError> {
+    let commitment: [u8; 32] = commitment.try_into()
+        .map_err(|_| ProofError::InvalidCommitment)?;
+    StateCommitment::try_from(commitment)
+        .map_err(|_| ProofError::InvalidCommitment)
+}
+
+// A decrypted keystore; the spend key stays on the Rust side.
+// Only its seed bytes are kept, and wiped on drop: penumbra's SpendKey does not
+// wipe itself, so one is derived per call and lives no longer than the call.
+#[derive(uniffi::Object)]
+pub struct UnlockedKeystore {
+    spend_key_bytes: Zeroizing<[u8; 32]>,
+}
+
+#[uniffi::export]
+impl UnlockedKeystore {
+    pub fn full_viewing_key(&self) -> String {
+        self.spend_key().full_viewing_key().to_string()
+    }
+
+    pub fn address(&self, index: u32) -> AddressData {
+        let (address, _) = self.spend_key().full_viewing_key().incoming().payment_address(index.into());
+        AddressData::from(&address)
+    }
+}
+
+impl UnlockedKeystore {
+    fn spend_key(&self) -> SpendKey {
+        SpendKey::from(SpendKeyBytes(*self.spend_key_bytes))
+    }
+}
+
+// Hands every counterparty an address of its own and remembers which one
+#[derive(uniffi::Object)]
+pub struct ContactAddressBook {
+    full_viewing_key: FullViewingKey,
+    allocator: Mutex<allocator::AddressAllocator>,
+}
+
+#[uniffi::export]
+impl ContactAddressBook {
+    #[uniffi::constructor]
+    pub fn new(full_viewing_key: String) -> Result<Arc<Self>, ProofError> {
+        Self::from_json(full_viewing_key, None)
+    }
+
+    // Restore a book saved with `to_json`
+    #[uniffi::constructor]
+    pub fn from_json(full_viewing_key: String, state: Option<String>) -> Result<Arc<Self>, ProofError> {
+        let full_viewing_key = FullViewingKey::from_str(&full_viewing_key)
+            .map_err(|_| ProofError::InvalidKey)?;
+        let allocator = match state {
+            Some(state) => serde_json::from_str(&state)
+                .map_err(|e| ProofError::InvalidAddressBook(e.to_string()))?,
+            None => allocator::AddressAllocator::default(),
+        };
+
+        Ok(Arc::new(Self {
+            full_viewing_key,
+            allocator: Mutex::new(allocator),
+        }))
+    }
+
+    pub fn address_for(&self, contact: String) -> Result<AddressData, ProofError> {
+        let index = self.allocator.lock().unwrap()
+            .allocate(&contact)
+            .ok_or(ProofError::AddressIndexExhausted)?;
+        let (address, _) = self.full_viewing_key.incoming().payment_address(index.into());
+
+        Ok(AddressData::from(&address))
+    }
+
+    pub fn index_of(&self, contact: String) -> Option<u32> {
+        self.allocator.lock().unwrap().index_of(&contact)
+    }
+
+    pub fn contact_of(&self, index: u32) -> Option<String> {
+        self.allocator.lock().unwrap().contact_of(index).map(str::to_string)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProofError> {
+        serde_json::to_string(&*self.allocator.lock().unwrap())
+            .map_err(|e| ProofError::InvalidAddressBook(e.to_string()))
+    }
+}
+
+// Splits a blob into an endless stream of QR parts, shown one after another
+#[derive(uniffi::Object)]
+pub struct MultipartEncoder {
+    encoder: Mutex<fountain::Encoder>,
+}
+
+#[uniffi::export]
+impl MultipartEncoder {
+    #[uniffi::constructor]
+    pub fn new(message: Vec<u8>, max_fragment_len: u32) -> Arc<Self> {
+        Arc::new(Self {
+            encoder: Mutex::new(fountain::Encoder::new(&message, max_fragment_len as usize)),
+        })
+    }
+
+    // Number of parts that carry the message once each
+    pub fn seq_len(&self) -> u32 {
+        self.encoder.lock().unwrap().seq_len()
+    }
+
+    pub fn next_part(&self) -> String {
+        self.encoder.lock().unwrap().next_part().encode()
+    }
+}
+
+// Reassembles a blob from scanned QR parts, in any order
+#[derive(uniffi::Object)]
+pub struct MultipartDecoder {
+    decoder: Mutex<fountain::Decoder>,
+}
+
+#[uniffi::export]
+impl MultipartDecoder {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            decoder: Mutex::new(fountain::Decoder::new()),
+        })
+    }
+
+    // Returns true once the whole message has been recovered. After a checksum
+    // error the decoder has started over; keep scanning from scratch
+    pub fn receive(&self, part: String) -> Result<bool, ProofError> {
+        let part = fountain::Part::decode(&part)?;
+        Ok(self.decoder.lock().unwrap().receive(&part)?)
+    }
+
+    pub fn progress(&self) -> f64 {
+        self.decoder.lock().unwrap().progress()
+    }
+
+    pub fn message(&self) -> Option<Vec<u8>> {
+        self.decoder.lock().unwrap().message().map(<[u8]>::to_vec)
+    }
+}
+
+#[derive(uniffi::Object)]
+pub struct ProofManager {
+    // Set when running watch-only from a full viewing key
+    full_viewing_key: Option<FullViewingKey>,
+    // Where stored spend keys live, if the app configured one
+    key_store: Option<Arc<dyn KeyStore>>,
+}
+
+#[uniffi::export]
+impl ProofManager {
+    #[uniffi::constructor]
+    pub fn new() -> Result<Arc<Self>, ProofError> {
+        Ok(Arc::new(Self {
+            full_viewing_key: None,
+            key_store: None,
+        }))
+    }
+
+    // Instance backed by any KeyStore implementation, e.g. a platform keychain
+    #[uniffi::constructor]
+    pub fn with_key_store(key_store: Arc<dyn KeyStore>) -> Arc<Self> {
+        Arc::new(Self {
+            full_viewing_key: None,
+            key_store: Some(key_store),
+        })
+    }
+
+    // Instance keeping password-encrypted spend keys in a directory
+    #[uniffi::constructor]
+    pub fn with_file_key_store(directory: String, password: String) -> Result<Arc<Self>, ProofError> {
+        let password = Zeroizing::new(password);
+        let key_store = FileKeyStore::new(directory, password.as_bytes())?;
+
+        Ok(Self::with_key_store(Arc::new(key_store)))
+    }
+
+    // Instance keeping spend keys in memory only
+    #[uniffi::constructor]
+    pub fn with_memory_key_store() -> Arc<Self> {
+        Self::with_key_store(Arc::new(MemoryKeyStore::new()))
+    }
+
+    // Watch-only instance that holds a full viewing key and no spending authority
+    #[uniffi::constructor]
+    pub fn from_full_viewing_key(full_viewing_key: String) -> Result<Arc<Self>, ProofError> {
+        let fvk = FullViewingKey::from_str(&full_viewing_key)
+            .map_err(|_| ProofError::InvalidKey)?;
+
+        Ok(Arc::new(Self {
+            full_viewing_key: Some(fvk),
+            key_store: None,
+        }))
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.full_viewing_key.is_some()
+    }
+
+    // Load the Groth16 keys of a circuit from its setup ceremony; provers need
+    // the proving key, verifiers only the verifying key
+    pub fn load_proving_key(&self, circuit: ProofCircuit, proving_key: Vec<u8>) -> Result<(), ProofError> {
+        Ok(proof::Circuit::from(circuit).load_proving_key(&proving_key)?)
+    }
+
+    pub fn load_verifying_key(&self, circuit: ProofCircuit, verifying_key: Vec<u8>) -> Result<(), ProofError> {
+        Ok(proof::Circuit::from(circuit).load_verifying_key(&verifying_key)?)
+    }
+
+
+    // Generate a fresh seed phrase from the OS RNG
+    pub fn generate_seed_phrase(&self, word_count: u8) -> Result<String, ProofError> {
+        let seed = match word_count {
+            12 => SeedPhrase::short_generate(OsRng),
+            24 => SeedPhrase::generate(OsRng),
+            _ => return Err(ProofError::InvalidSeedLength(word_count as u32)),
+        };
+
+        Ok(seed.to_string())
+    }
+
+    // Check a seed phrase, reporting exactly what is wrong with it
+    pub fn validate_seed_phrase(&self, seed_phrase: String) -> Result<(), ProofError> {
+        parse_seed_phrase(seed_phrase).map(|_| ())
+    }
+
+    // Split the seed entropy into Shamir shares, any `threshold` of which restore it
+    pub fn split_seed(&self, seed_phrase: String, threshold: u8, shares: u8) -> Result<Vec<String>, ProofError> {
+        let entropy = Zeroizing::new(parse_mnemonic(seed_phrase)?.to_entropy());
+
+        Ok(backup::split(&entropy, threshold, shares)?)
+    }
+
+    // Restore a seed phrase from Shamir shares
+    pub fn combine_shares(&self, shares: Vec<String>) -> Result<String, ProofError> {
+        let entropy = backup::combine(&shares)?;
+        let mnemonic = bip39::Mnemonic::from_entropy(&entropy)
+            .map_err(|e| ProofError::InvalidShare(e.to_string()))?;
+
+        // Shares may carry entropy of any length; only seed phrase lengths are accepted
+        let word_count = mnemonic.word_count();
+        if word_count != 12 && word_count != 24 {
+            return Err(ProofError::InvalidSeedLength(word_count as u32));
+        }
+        Ok(mnemonic.to_string())
+    }
+
+    // Key Generation
+    fn generate_keys(&self, seed_phrase: String, bip44_account: u32) -> Result<KeyPair, ProofError> {
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+        let fvk = spend_key.full_viewing_key();
+
+        Ok(KeyPair {
+            spend_key: spend_key.to_bytes().0.to_vec(),
+            view_key: full_viewing_key_to_bytes(fvk),
+            full_viewing_key: fvk.to_string(),
+        })
+    }
+
+    // Convert between the raw and bech32m encodings of a full viewing key
+    pub fn encode_full_viewing_key(&self, view_key: Vec<u8>) -> Result<String, ProofError> {
+        Ok(full_viewing_key_from_bytes(&view_key)?.to_string())
+    }
+
+    pub fn decode_full_viewing_key(&self, full_viewing_key: String) -> Result<Vec<u8>, ProofError> {
+        let fvk = FullViewingKey::from_str(&full_viewing_key)
+            .map_err(|_| ProofError::InvalidKey)?;
+
+        Ok(full_viewing_key_to_bytes(&fvk))
+    }
+
+     // Address Generation
+     fn generate_address(&self, spend_key_bytes: Vec<u8>, index: u32) -> Result<AddressData, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        Ok(self.address_with(spend_key.full_viewing_key(), index))
+    }
+
+    // Address of a given account of a seed phrase
+    pub fn generate_account_address(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        index: u32,
+    ) -> Result<AddressData, ProofError> {
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        Ok(self.address_with(spend_key.full_viewing_key(), index))
+    }
+
+    // Walk the accounts of a seed phrase and return the first one without activity
+    pub fn discover_accounts(
+        &self,
+        seed_phrase: String,
+        activity: Box<dyn AccountActivity>,
+    ) -> Result<u32, ProofError> {
+        // Every account gets its own copy of the phrase, wiped once it is parsed
+        let seed_phrase = Zeroizing::new(parse_mnemonic(seed_phrase)?.to_string());
+        for bip44_account in 0..MAX_DISCOVERED_ACCOUNTS {
+            let spend_key = spend_key_from_seed(seed_phrase.to_string(), bip44_account)?;
+            let fvk = spend_key.full_viewing_key().to_string();
+
+            if !activity.is_account_used(bip44_account, fvk) {
+                return Ok(bip44_account);
+            }
+        }
+
+        Err(ProofError::NoUnusedAccount)
+    }
+
+
+    // Standard bech32m encoding of an address, as used by Penumbra tooling
+    pub fn address_to_string(&self, address: AddressData) -> Result<String, ProofError> {
+        Ok(address.to_address()?.to_string())
+    }
+
+    pub fn address_from_string(&self, address: String) -> Result<AddressData, ProofError> {
+        let address = Address::from_str(address.trim())
+            .map_err(|_| ProofError::InvalidAddress)?;
+
+        Ok(AddressData::from(&address))
+    }
+
+    // Truncated form for display only; it cannot be parsed back
+    pub fn address_short_form(&self, address: AddressData) -> Result<String, ProofError> {
+        Ok(address.to_address()?.display_short_form())
+    }
+
+    // One-time address with a random diversifier, unlinkable to the account's other addresses
+    pub fn generate_ephemeral_address(&self, spend_key_bytes: Vec<u8>, address_index: u32) -> Result<AddressData, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        Ok(self.ephemeral_address_with(spend_key.full_viewing_key(), address_index))
+    }
+
+    pub fn watch_ephemeral_address(&self, address_index: u32) -> Result<AddressData, ProofError> {
+        Ok(self.ephemeral_address_with(self.viewing_key()?, address_index))
+    }
+
+    // Which of our address indexes produced `address`, if any
+    pub fn address_index_of(
+        &self,
+        full_viewing_key: String,
+        address: AddressData,
+    ) -> Result<Option<AddressIndexData>, ProofError> {
+        let fvk = FullViewingKey::from_str(&full_viewing_key)
+            .map_err(|_| ProofError::InvalidKey)?;
+
+        self.address_index_with(&fvk, &address)
+    }
+
+    pub fn watch_address_index_of(&self, address: AddressData) -> Result<Option<AddressIndexData>, ProofError> {
+        self.address_index_with(self.viewing_key()?, &address)
+    }
+
+    // Create Note
+    pub fn create_note(
+        &self,
+        debtor_address: AddressData,
+        creditor_address: AddressData,
+        amount: u64,
+        asset_id: u64,
+    ) -> Result<Note, ProofError> {
+        let note = self.build_note(&debtor_address, &creditor_address, amount, asset_id)?;
+
+        Note::try_from(&note)
+    }
+
+    // Check that a note opening really commits to `commitment`
+    pub fn verify_note_commitment(&self, note_opening: Note, commitment: Vec<u8>) -> Result<(), ProofError> {
+        note_opening.verify_commitment(&commitment)
+    }
+
+    // Export the full note opening as canonical JSON or binary
+    pub fn export_note_json(&self, note: Note) -> Result<String, ProofError> {
+        serde_json::to_string(&note.to_note()?)
+            .map_err(|e| ProofError::NoteError(e.to_string()))
+    }
+
+    pub fn export_note_bytes(&self, note: Note) -> Result<Vec<u8>, ProofError> {
+        Ok(note.to_note()?.to_bytes())
+    }
+
+    // Import a note opening; the commitment is recomputed from its fields
+    pub fn import_note_json(&self, json: String) -> Result<Note, ProofError> {
+        let note: note::Note = serde_json::from_str(&json)
+            .map_err(|e| ProofError::NoteError(e.to_string()))?;
+
+        Note::try_from(&note)
+    }
+
+    pub fn import_note_bytes(&self, bytes: Vec<u8>) -> Result<Note, ProofError> {
+        let note = note::Note::from_bytes(&bytes)
+            .map_err(|e| ProofError::NoteError(e.to_string()))?;
+
+        Note::try_from(&note)
+    }
+
+    // Create Note with an rseed derived from the debtor's spend key, both addresses,
+    // the value and a per-note counter, so a restored wallet can rebuild it exactly.
+    // The counter must be unique per debtor and creditor address; the app stores it
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_deterministic_note(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        debtor_address: AddressData,
+        creditor_address: AddressData,
+        amount: u64,
+        asset_id: u64,
+        counter: u64,
+    ) -> Result<Note, ProofError> {
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        // Only the debtor's key may be used to derive the rseed
+        let debtor = debtor_address.to_address()?;
+        if !spend_key.full_viewing_key().incoming().views_address(&debtor) {
+            return Err(ProofError::NotDebtor);
+        }
+
+        let rseed = note::derive_rseed(
+            &spend_key,
+            &debtor,
+            &creditor_address.to_address()?,
+            value_from_parts(amount, asset_id),
+            counter,
+        );
+        let note = note_from_parts(&debtor_address, &creditor_address, amount, asset_id, rseed)?;
+
+        Note::try_from(&note)
+    }
+
+    // Build a note paying a payment request, given by its URI; the request is
+    // verified here so an unsigned or expired one can never be paid
+    pub fn create_note_from_request(
+        &self,
+        debtor_address: AddressData,
+        uri: String,
+    ) -> Result<Note, ProofError> {
+        let request = self.verified_request(request::SignedPaymentRequest::from_uri(&uri)?)?;
+
+        self.create_note(debtor_address, request.creditor_address, request.amount, request.asset_id)
+    }
+
+    // Sign a payment request as its creditor; returns the compact binary form
+    pub fn sign_payment_request(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        request: PaymentRequestData,
+    ) -> Result<Vec<u8>, ProofError> {
+        self.ensure_can_sign()?;
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        Ok(request.to_request()?.sign(&spend_key)?.encode()?)
+    }
+
+    // The pocketlib: URI of a signed payment request
+    pub fn payment_request_uri(&self, signed_request: Vec<u8>) -> Result<String, ProofError> {
+        Ok(request::SignedPaymentRequest::decode(&signed_request)?.to_uri()?)
+    }
+
+    // Parse and verify a payment request from its URI or compact binary form
+    pub fn parse_payment_request(&self, uri: String) -> Result<PaymentRequestData, ProofError> {
+        self.verified_request(request::SignedPaymentRequest::from_uri(&uri)?)
+    }
+
+    pub fn parse_payment_request_bytes(&self, signed_request: Vec<u8>) -> Result<PaymentRequestData, ProofError> {
+        self.verified_request(request::SignedPaymentRequest::decode(&signed_request)?)
+    }
+
+    // Sign Note
+    pub fn sign_note(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        note: Note,
+    ) -> Result<SignedNote, ProofError> {
+        Ok(self.sign_note_for_holder(seed_phrase, bip44_account, note)?.signed_note)
+    }
+
+    // Sign Note, also returning the randomizer the signer needs to later show
+    // that the note's verification key is theirs
+    pub fn sign_note_for_holder(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        note: Note,
+    ) -> Result<HolderSignedNote, ProofError> {
+        self.ensure_can_sign()?;
+
+        // Generate spend key from seed phrase
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        self.sign_note_with(&spend_key, note)
+    }
+
+    // Encrypt the spend key of an account under a password
+    pub fn create_keystore(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        password: String,
+    ) -> Result<Vec<u8>, ProofError> {
+        let password = Zeroizing::new(password);
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        keystore::encrypt(&spend_key, password.as_bytes(), keystore::KdfParams::default())
+            .map_err(ProofError::from)
+    }
+
+    // Decrypt a keystore into a handle that never exposes the spend key
+    pub fn unlock(&self, keystore: Vec<u8>, password: String) -> Result<Arc<UnlockedKeystore>, ProofError> {
+        let password = Zeroizing::new(password);
+        let spend_key = keystore::decrypt(&keystore, password.as_bytes())?;
+
+        Ok(Arc::new(UnlockedKeystore {
+            spend_key_bytes: Zeroizing::new(spend_key.to_bytes().0),
+        }))
+    }
+
+    // Sign Note with an unlocked keystore
+    pub fn sign_note_with_keystore(
+        &self,
+        keystore: Arc<UnlockedKeystore>,
+        note: Note,
+    ) -> Result<SignedNote, ProofError> {
+        self.ensure_can_sign()?;
+
+        Ok(self.sign_note_with(&keystore.spend_key(), note)?.signed_note)
+    }
+
+    // Save the spend key of an account in the key store
+    pub fn store_key(&self, id: String, seed_phrase: String, bip44_account: u32) -> Result<(), ProofError> {
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        self.key_store()?.store(id, spend_key.to_bytes().0.to_vec())
+    }
+
+    pub fn delete_key(&self, id: String) -> Result<(), ProofError> {
+        self.key_store()?.delete(id)
+    }
+
+    pub fn list_keys(&self) -> Result<Vec<String>, ProofError> {
+        self.key_store()?.list()
+    }
+
+    pub fn stored_full_viewing_key(&self, id: String) -> Result<String, ProofError> {
+        self.key_store()?.full_viewing_key(id)
+    }
+
+    // Sign Note with a key from the key store, which does the signing itself
+    pub fn sign_note_with_stored_key(&self, id: String, note: Note) -> Result<SignedNote, ProofError> {
+        self.ensure_can_sign()?;
+        let key_store = self.key_store()?;
+        let fvk = FullViewingKey::from_str(&key_store.full_viewing_key(id.clone())?)
+            .map_err(|_| ProofError::InvalidKey)?;
 
-//         // Generate addresses
-//         let debtor_address = manager.generate_address(keys.spend_key.clone(), 1)?;
-//         let creditor_keys = manager.generate_keys(
-//             "word word word word word word word word word word word word".to_string()
-//         )?;
-//         let creditor_address = manager.generate_address(creditor_keys.spend_key, 1)?;
+        Ok(self.sign_note_as(&fvk, note, |randomizer, commitment| {
+            let signature = key_store.sign(id, randomizer.to_bytes().to_vec(), commitment.to_vec())?;
+            Signature::try_from(signature.as_slice()).map_err(|_| ProofError::InvalidSignature)
+        })?.signed_note)
+    }
+
+       // Verify the signature over a note's commitment. The fields must open that
+       // commitment, or the signature says nothing about them. It does not check
+       // that rk belongs to the debtor; verify_signed_note does
+       pub fn verify_signature(
+        &self,
+        verification_key_bytes: Vec<u8>,
+        note: Note,
+        signature: Vec<u8>,
+    ) -> Result<bool, ProofError> {
+        let rk = VerificationKey::<SpendAuth>::try_from(verification_key_bytes.as_slice())
+            .map_err(|_| ProofError::InvalidKey)?;
+
+        let sig = Signature::try_from(signature.as_slice())
+            .map_err(|_| ProofError::InvalidSignature)?;
+
+        match note.verify_commitment(&note.commitment) {
+            Ok(()) => {}
+            Err(ProofError::CommitmentMismatch(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        }
+
+        // Map the verification result to a bool
+        Ok(rk.verify(&note.commitment, &sig).is_ok())
+    }
+
+    // Verify a signed note: the fields must open the signed commitment, the signature
+    // must hold and its rk must belong to the debtor. Any of these failing is false;
+    // only inputs that do not decode are errors
+    pub fn verify_signed_note(
+        &self,
+        signed_note: SignedNote,
+    ) -> Result<bool, ProofError> {
+        let rk = VerificationKey::<SpendAuth>::try_from(signed_note.verification_key.as_slice())
+            .map_err(|_| ProofError::InvalidKey)?;
+        let sig = Signature::try_from(signed_note.signature.as_slice())
+            .map_err(|_| ProofError::InvalidSignature)?;
+        let authorization_proof = AuthorizationProof::try_from(signed_note.authorization_proof.as_slice())
+            .map_err(|_| ProofError::InvalidProof)?;
+        let debtor = signed_note.note.debtor_address.to_address()?;
+
+        // The signature only covers the commitment, so the fields must match it
+        match signed_note.note.verify_commitment(&signed_note.note.commitment) {
+            Ok(()) => {}
+            Err(ProofError::CommitmentMismatch(_)) => return Ok(false),
+            Err(e) => return Err(e),
+        }
+
+        if rk.verify(&signed_note.note.commitment, &sig).is_err() {
+            return Ok(false);
+        }
+
+        proof_verified(authorization_proof.verify(AuthorizationProofPublic { rk, debtor }))
+    }
+
+    // Spend verification key (ak) of a spend key, which a signer discloses
+    // together with a randomizer to show that an rk is theirs
+    pub fn spend_verification_key(&self, spend_key_bytes: Vec<u8>) -> Result<Vec<u8>, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        Ok(spend_key.full_viewing_key().spend_verification_key().to_bytes().to_vec())
+    }
+
+    // Check that rk is the spend verification key randomized by the given randomizer
+    pub fn verify_randomized_key(
+        &self,
+        spend_verification_key: Vec<u8>,
+        spend_auth_randomizer: Vec<u8>,
+        rk: Vec<u8>,
+    ) -> Result<bool, ProofError> {
+        let ak = VerificationKey::<SpendAuth>::try_from(spend_verification_key.as_slice())
+            .map_err(|_| ProofError::InvalidKey)?;
+        let rk = VerificationKey::<SpendAuth>::try_from(rk.as_slice())
+            .map_err(|_| ProofError::InvalidKey)?;
+        let randomizer = randomizer_from_bytes(&spend_auth_randomizer)?;
+
+        Ok(ak.randomize(&randomizer).to_bytes() == rk.to_bytes())
+    }
+
+    // Create a note and prove we know its opening
+    pub fn prove_note_opening(
+        &self,
+        debtor_address: AddressData,
+        creditor_address: AddressData,
+        amount: u64,
+        asset_id: u64,
+    ) -> Result<ProvenNote, ProofError> {
+        let note = self.build_note(&debtor_address, &creditor_address, amount, asset_id)?;
+        let note_commitment = note.commit();
+        let note_record = Note::try_from(&note)?;
+
+        let mut rng = rand::thread_rng();
+        let proof = NoteOpeningProof::prove(
+            Fq::rand(&mut rng),
+            Fq::rand(&mut rng),
+            NoteOpeningProofPublic { note_commitment },
+            NoteOpeningProofPrivate { note },
+        ).map_err(proof_generation_error)?;
+
+        Ok(ProvenNote {
+            note: note_record,
+            proof: proof.to_bytes().to_vec(),
+        })
+    }
+
+    // Verify a note opening proof against a public commitment
+    pub fn verify_note_opening(
+        &self,
+        proof: Vec<u8>,
+        commitment: Vec<u8>,
+    ) -> Result<bool, ProofError> {
+        let proof = NoteOpeningProof::try_from(proof.as_slice())
+            .map_err(|_| ProofError::InvalidProof)?;
+        let note_commitment = commitment_from_bytes(&commitment)?;
+
+        proof_verified(proof.verify(NoteOpeningProofPublic { note_commitment }))
+    }
+
+    // Create IntentAction
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_intent_action(
+        &self,
+        seed_phrase: String,
+        bip44_account: u32,
+        amount: u64,
+        asset_id: u64,
+        address_index: u32,
+        creditor_address: AddressData,
+        clue_precision: Option<u8>,
+    ) -> Result<IntentAction, ProofError> {
+        self.ensure_can_sign()?;
+
+        let spend_key = spend_key_from_seed(seed_phrase, bip44_account)?;
+
+        // The debtor pays from the requested address of their own wallet
+        let (debtor_addr, _) = spend_key.full_viewing_key().incoming().payment_address(address_index.into());
+        let debtor_address = AddressData::from(&debtor_addr);
+
+        let note = self.build_note(&debtor_address, &creditor_address, amount, asset_id)?;
+        let note_commitment = note.commit();
+        let note_commitment_bytes = note_commitment.0.to_bytes();
+
+        // Authorize the commitment with a randomized spend auth key
+        let (auth_sig, rk, spend_auth_randomizer) = self.sign_commitment(&spend_key, &note_commitment_bytes);
+
+        // Encrypt the note to the creditor, and to ourselves for recovery
+        let note_ciphertext = note.encrypt()
+            .map_err(|e| ProofError::NoteError(e.to_string()))?;
+        let aux_ciphertext = note.encrypt_outgoing(spend_key.full_viewing_key().outgoing())
+            .map_err(|e| ProofError::NoteError(e.to_string()))?;
+
+        // Optionally let a relay pre-filter the note for the creditor
+        let clue = match clue_precision {
+            Some(precision_bits) => note.create_clue(precision_bits)
+                .map_err(|e| ProofError::ClueError(e.to_string()))?
+                .0
+                .to_vec(),
+            None => Vec::new(),
+        };
+
+        // Prove we can open the commitment and that rk belongs to its debtor
+        let fvk = spend_key.full_viewing_key();
+        let mut rng = rand::thread_rng();
+        let zkp = IntentProof::prove(
+            Fq::rand(&mut rng),
+            Fq::rand(&mut rng),
+            IntentProofPublic { note_commitment, rk },
+            IntentProofPrivate {
+                note,
+                ak: *fvk.spend_verification_key(),
+                nk: *fvk.nullifier_key(),
+                spend_auth_randomizer: *spend_auth_randomizer,
+            },
+        ).map_err(proof_generation_error)?;
+
+        Ok(IntentAction {
+            note_commitment: note_commitment_bytes.to_vec(),
+            auth_sig: Vec::<u8>::from(auth_sig),
+            rk: rk.to_bytes().to_vec(),
+            zkp: zkp.to_bytes().to_vec(),
+            note_ciphertext,
+            aux_ciphertext,
+            clue,
+        })
+    }
+
+    // Verify IntentAction: the signature must hold, and the proof must show that
+    // rk belongs to the debtor of the committed note
+    pub fn verify_intent_action(
+        &self,
+        action: IntentAction,
+    ) -> Result<bool, ProofError> {
+        let note_commitment = commitment_from_bytes(&action.note_commitment)?;
+
+        let rk = VerificationKey::<SpendAuth>::try_from(action.rk.as_slice())
+            .map_err(|_| ProofError::InvalidKey)?;
+        let auth_sig = Signature::try_from(action.auth_sig.as_slice())
+            .map_err(|_| ProofError::InvalidSignature)?;
+        let zkp = IntentProof::try_from(action.zkp.as_slice())
+            .map_err(|_| ProofError::InvalidProof)?;
+
+        if rk.verify(&action.note_commitment, &auth_sig).is_err() {
+            return Ok(false);
+        }
+
+        proof_verified(zkp.verify(IntentProofPublic { note_commitment, rk }))
+    }
+
+    // Decrypt a note sent to one of our addresses
+    pub fn decrypt_note(
+        &self,
+        spend_key_bytes: Vec<u8>,
+        ciphertext: Vec<u8>,
+    ) -> Result<Note, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        self.decrypt_note_with(spend_key.full_viewing_key(), &ciphertext)
+    }
+
+    // Recover a note we issued from its aux ciphertext
+    pub fn decrypt_outgoing_note(
+        &self,
+        spend_key_bytes: Vec<u8>,
+        commitment: Vec<u8>,
+        aux_ciphertext: Vec<u8>,
+    ) -> Result<Note, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        self.decrypt_outgoing_note_with(spend_key.full_viewing_key(), &commitment, &aux_ciphertext)
+    }
+
+    // Create an FMD clue for a creditor address
+    pub fn create_clue(
+        &self,
+        creditor_address: AddressData,
+        precision_bits: u8,
+    ) -> Result<Vec<u8>, ProofError> {
+        let creditor = creditor_address.to_address()?;
+        let clue = note::clue::create_clue(creditor.clue_key(), precision_bits)
+            .map_err(|e| ProofError::ClueError(e.to_string()))?;
+
+        Ok(clue.0.to_vec())
+    }
+
+    // Detection key of one of our addresses, safe to hand to a relay
+    pub fn detection_key(&self, spend_key_bytes: Vec<u8>, index: u32) -> Result<Vec<u8>, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        Ok(self.detection_key_with(spend_key.full_viewing_key(), index))
+    }
+
+    // Check whether a clue may be for the holder of a detection key
+    pub fn detect(&self, detection_key: Vec<u8>, clue: Vec<u8>) -> Result<bool, ProofError> {
+        let detection_key: [u8; 32] = detection_key.try_into()
+            .map_err(|_| ProofError::InvalidKey)?;
+        let detection_key = fmd::DetectionKey::from_field(
+            Fr::from_bytes_checked(&detection_key).map_err(|_| ProofError::InvalidKey)?,
+        );
+        let clue = fmd::Clue(clue.try_into()
+            .map_err(|_| ProofError::ClueError("invalid clue length".to_string()))?);
+
+        Ok(note::clue::detect(&detection_key, &clue))
+    }
+
+    // Scan a batch of note ciphertexts for notes sent to any of our addresses
+    pub fn scan_notes(
+        &self,
+        spend_key_bytes: Vec<u8>,
+        ciphertexts: Vec<Vec<u8>>,
+    ) -> Result<Vec<ScannedNote>, ProofError> {
+        let spend_key = spend_key_from_bytes(spend_key_bytes)?;
+
+        self.scan_notes_with(spend_key.full_viewing_key(), &ciphertexts)
+    }
+
+    // Watch-only counterparts, driven by the full viewing key we were built with
+    pub fn watch_address(&self, index: u32) -> Result<AddressData, ProofError> {
+        Ok(self.address_with(self.viewing_key()?, index))
+    }
+
+    pub fn watch_decrypt_note(&self, ciphertext: Vec<u8>) -> Result<Note, ProofError> {
+        self.decrypt_note_with(self.viewing_key()?, &ciphertext)
+    }
+
+    pub fn watch_decrypt_outgoing_note(
+        &self,
+        commitment: Vec<u8>,
+        aux_ciphertext: Vec<u8>,
+    ) -> Result<Note, ProofError> {
+        self.decrypt_outgoing_note_with(self.viewing_key()?, &commitment, &aux_ciphertext)
+    }
+
+    pub fn watch_detection_key(&self, index: u32) -> Result<Vec<u8>, ProofError> {
+        Ok(self.detection_key_with(self.viewing_key()?, index))
+    }
+
+    pub fn watch_scan_notes(&self, ciphertexts: Vec<Vec<u8>>) -> Result<Vec<ScannedNote>, ProofError> {
+        self.scan_notes_with(self.viewing_key()?, &ciphertexts)
+    }
+
+}
+
+// Private helpers
+impl ProofManager {
+    fn verified_request(&self, signed: request::SignedPaymentRequest) -> Result<PaymentRequestData, ProofError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        signed.verify(now)?;
+
+        Ok(PaymentRequestData::from(&signed.request))
+    }
+
+    fn ensure_can_sign(&self) -> Result<(), ProofError> {
+        if self.is_watch_only() {
+            return Err(ProofError::WatchOnly);
+        }
+        Ok(())
+    }
+
+    fn key_store(&self) -> Result<&Arc<dyn KeyStore>, ProofError> {
+        self.key_store.as_ref().ok_or(ProofError::MissingKeyStore)
+    }
+
+    fn sign_note_with(&self, spend_key: &SpendKey, note: Note) -> Result<HolderSignedNote, ProofError> {
+        self.sign_note_as(spend_key.full_viewing_key(), note, |randomizer, commitment| {
+            Ok(secret::sign_randomized(spend_key, randomizer, commitment))
+        })
+    }
+
+    // Sign a note as the holder of `fvk`; `sign` holds the spend key and signs
+    // the commitment under the randomizer it is given
+    fn sign_note_as(
+        &self,
+        fvk: &FullViewingKey,
+        note: Note,
+        sign: impl FnOnce(&Fr, &[u8]) -> Result<Signature<SpendAuth>, ProofError>,
+    ) -> Result<HolderSignedNote, ProofError> {
+        // Only the debtor may sign
+        let debtor = note.debtor_address.to_address()?;
+        if !fvk.incoming().views_address(&debtor) {
+            return Err(ProofError::NotDebtor);
+        }
+
+        // Never sign a commitment that does not match the note being shown
+        note.verify_commitment(&note.commitment)?;
+
+        // A fresh randomizer per signature keeps every rk unlinkable
+        let spend_auth_randomizer = secret::Secret(Fr::rand(&mut OsRng));
+        let rk = fvk.spend_verification_key().randomize(&spend_auth_randomizer);
+        let signature = sign(&spend_auth_randomizer, &note.commitment)?;
+
+        // Prove that rk belongs to the debtor address
+        let mut rng = rand::thread_rng();
+        let authorization_proof = AuthorizationProof::prove(
+            Fq::rand(&mut rng),
+            Fq::rand(&mut rng),
+            AuthorizationProofPublic { rk, debtor },
+            AuthorizationProofPrivate {
+                ak: *fvk.spend_verification_key(),
+                nk: *fvk.nullifier_key(),
+                spend_auth_randomizer: *spend_auth_randomizer,
+            },
+        ).map_err(proof_generation_error)?;
+
+        Ok(HolderSignedNote {
+            signed_note: SignedNote {
+                note,
+                signature: Vec::<u8>::from(signature),
+                verification_key: rk.to_bytes().to_vec(),
+                authorization_proof: authorization_proof.to_bytes().to_vec(),
+            },
+            spend_auth_randomizer: spend_auth_randomizer.to_bytes().to_vec(),
+        })
+    }
+
+    fn viewing_key(&self) -> Result<&FullViewingKey, ProofError> {
+        self.full_viewing_key.as_ref().ok_or(ProofError::MissingViewingKey)
+    }
+
+    fn address_with(&self, fvk: &FullViewingKey, index: u32) -> AddressData {
+        let (address, _) = fvk.incoming().payment_address(index.into());
+        AddressData::from(&address)
+    }
+
+    fn ephemeral_address_with(&self, fvk: &FullViewingKey, address_index: u32) -> AddressData {
+        let (address, _) = fvk.incoming().ephemeral_address(OsRng, address_index.into());
+        AddressData::from(&address)
+    }
+
+    fn address_index_with(
+        &self,
+        fvk: &FullViewingKey,
+        address: &AddressData,
+    ) -> Result<Option<AddressIndexData>, ProofError> {
+        let address = address.to_address()?;
+        let ivk = fvk.incoming();
+
+        // The diversifier alone decrypts to some index for any key; only
+        // views_address checks that the transmission key is really ours
+        if !ivk.views_address(&address) {
+            return Ok(None);
+        }
+
+        Ok(Some(ivk.index_for_diversifier(address.diversifier()).into()))
+    }
+
+    fn detection_key_with(&self, fvk: &FullViewingKey, index: u32) -> Vec<u8> {
+        let (_, detection_key) = fvk.incoming().payment_address(index.into());
+        detection_key.to_bytes().to_vec()
+    }
+
+    fn decrypt_note_with(&self, fvk: &FullViewingKey, ciphertext: &[u8]) -> Result<Note, ProofError> {
+        let note = note::Note::decrypt(ciphertext, fvk.incoming())
+            .map_err(|_| ProofError::DecryptionFailed)?;
+
+        Note::try_from(&note)
+    }
+
+    fn decrypt_outgoing_note_with(
+        &self,
+        fvk: &FullViewingKey,
+        commitment: &[u8],
+        aux_ciphertext: &[u8],
+    ) -> Result<Note, ProofError> {
+        let commitment = commitment_from_bytes(commitment)?;
+        let note = note::Note::decrypt_outgoing(aux_ciphertext, fvk.outgoing(), &commitment)
+            .map_err(|_| ProofError::DecryptionFailed)?;
+
+        Note::try_from(&note)
+    }
+
+    fn scan_notes_with(
+        &self,
+        fvk: &FullViewingKey,
+        ciphertexts: &[Vec<u8>],
+    ) -> Result<Vec<ScannedNote>, ProofError> {
+        // Anyone who knows one of our addresses can send a note whose amount or
+        // asset id does not fit the FFI record; skip it rather than fail the batch
+        Ok(note::scan::scan_notes(fvk.incoming(), ciphertexts)
+            .into_iter()
+            .filter_map(|scanned| Some(ScannedNote {
+                note: Note::try_from(&scanned.note).ok()?,
+                address_index: scanned.address_index.into(),
+                position: scanned.position as u32,
+            }))
+            .collect())
+    }
+
+    fn build_note(
+        &self,
+        debtor_address: &AddressData,
+        creditor_address: &AddressData,
+        amount: u64,
+        asset_id: u64,
+    ) -> Result<note::Note, ProofError> {
+        // Generated in place so the note holds the only copy, which it wipes
+        note_from_parts(debtor_address, creditor_address, amount, asset_id, Rseed::generate(&mut OsRng))
+    }
+
+    fn sign_commitment(
+        &self,
+        spend_key: &SpendKey,
+        commitment: &[u8],
+    ) -> (Signature<SpendAuth>, VerificationKey<SpendAuth>, secret::Secret<Fr>) {
+        // A fresh randomizer per signature keeps every rk unlinkable
+        let spend_auth_randomizer = secret::Secret(Fr::rand(&mut OsRng));
+        let rk = spend_key.spend_auth_key().randomize(&spend_auth_randomizer).into();
+
+        // Sign the commitment
+        (secret::sign_randomized(spend_key, &spend_auth_randomizer, commitment), rk, spend_auth_randomizer)
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEBTOR_SEED: &str = "test test test test test test test test test test test junk";
+    const CREDITOR_SEED: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    // Tests load the dev setup through the public API, as the app loads the
+    // ceremony keys at startup; nothing falls back to dev keys on its own
+    fn load_test_parameters(manager: &ProofManager) {
+        static LOADED: std::sync::Once = std::sync::Once::new();
+        LOADED.call_once(|| {
+            for circuit in [ProofCircuit::NoteOpening, ProofCircuit::Authorization, ProofCircuit::Intent] {
+                manager.load_proving_key(circuit, proof::Circuit::from(circuit).dev_proving_key())
+                    .expect("dev parameters load");
+            }
+        });
+    }
+
+    fn test_manager() -> Result<Arc<ProofManager>, ProofError> {
+        let manager = ProofManager::new()?;
+        load_test_parameters(&manager);
+        Ok(manager)
+    }
+
+    fn signed_test_note(manager: &ProofManager) -> Result<SignedNote, ProofError> {
+        // Generate addresses
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let debtor_address = manager.generate_address(keys.spend_key, 1)?;
+        let creditor_keys = manager.generate_keys(CREDITOR_SEED.to_string(), 0)?;
+        let creditor_address = manager.generate_address(creditor_keys.spend_key, 1)?;
+
+        // Create and sign note
+        let note = manager.create_note(debtor_address, creditor_address, 30u64, 1u64)?;
+        manager.sign_note(DEBTOR_SEED.to_string(), 0, note)
+    }
+
+    #[test]
+    fn test_full_flow() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let signed = signed_test_note(&manager)?;
+
+        // Verify signature
+        assert!(manager.verify_signature(
+            signed.verification_key.clone(),
+            signed.note.clone(),
+            signed.signature.clone(),
+        )?);
+
+        // The signature does not carry over to other fields under the same commitment
+        let mut inflated = signed.note.clone();
+        inflated.amount = 3_000;
+        assert!(!manager.verify_signature(
+            signed.verification_key.clone(),
+            inflated,
+            signed.signature.clone(),
+        )?);
+        assert!(manager.verify_signed_note(signed)?);
+
+        Ok(())
+    }
+
+    // Without the dev-parameters feature there is no fallback: signing and
+    // verifying work only because the keys were loaded with load_proving_key
+    #[cfg(not(feature = "dev-parameters"))]
+    #[test]
+    fn test_signing_with_loaded_parameters() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let signed = signed_test_note(&manager)?;
+        assert!(manager.verify_signed_note(signed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_from_non_debtor_rejected() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let mut signed = signed_test_note(&manager)?;
+
+        // Claim the note was issued by the creditor instead; the swapped
+        // fields no longer open the signed commitment
+        std::mem::swap(&mut signed.note.debtor_address, &mut signed.note.creditor_address);
+        assert!(!manager.verify_signed_note(signed)?);
+
+        // Nor can anyone but the debtor sign in the first place
+        let note = signed_test_note(&manager)?.note;
+        assert!(matches!(
+            manager.sign_note(CREDITOR_SEED.to_string(), 0, note),
+            Err(ProofError::NotDebtor)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_intent_action_binds_debtor() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let creditor_keys = manager.generate_keys(CREDITOR_SEED.to_string(), 0)?;
+        let creditor_address = manager.generate_address(creditor_keys.spend_key.clone(), 1)?;
+
+        let mut action = manager.create_intent_action(
+            DEBTOR_SEED.to_string(), 0, 30, 1, 1, creditor_address, None,
+        )?;
+        assert!(manager.verify_intent_action(IntentAction {
+            note_commitment: action.note_commitment.clone(),
+            auth_sig: action.auth_sig.clone(),
+            rk: action.rk.clone(),
+            zkp: action.zkp.clone(),
+            note_ciphertext: Vec::new(),
+            aux_ciphertext: Vec::new(),
+            clue: Vec::new(),
+        })?);
+
+        // A valid signature by someone else does not come with a valid proof
+        let other = spend_key_from_bytes(creditor_keys.spend_key)?;
+        let (auth_sig, rk, _) = manager.sign_commitment(&other, &action.note_commitment);
+        action.auth_sig = Vec::<u8>::from(auth_sig);
+        action.rk = rk.to_bytes().to_vec();
+        assert!(!manager.verify_intent_action(action)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_skips_unrepresentable_notes() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(CREDITOR_SEED.to_string(), 0)?;
+        let ours = manager.generate_address(keys.spend_key.clone(), 1)?.to_address()?;
+
+        let mut rng = rand::thread_rng();
+        let mut note_with_asset = |asset_id: Fq| note::Note::from_parts(
+            Address::dummy(&mut rand::thread_rng()),
+            ours.clone(),
+            Value { amount: 10u64.into(), asset_id: asset::Id(asset_id) },
+            Rseed::generate(&mut rng),
+        ).and_then(|note| note.encrypt());
+
+        // A real Penumbra asset id is a full field element
+        let ciphertexts = vec![
+            note_with_asset(Fq::from(u64::MAX) * Fq::from(u64::MAX))
+                .map_err(|e| ProofError::NoteError(e.to_string()))?,
+            note_with_asset(Fq::from(1u64))
+                .map_err(|e| ProofError::NoteError(e.to_string()))?,
+        ];
+
+        let scanned = manager.scan_notes(keys.spend_key, ciphertexts)?;
+        assert_eq!(scanned.len(), 1);
+        assert_eq!(scanned[0].position, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_viewing_key_encodings() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+
+        assert_eq!(manager.encode_full_viewing_key(keys.view_key.clone())?, keys.full_viewing_key);
+        assert_eq!(manager.decode_full_viewing_key(keys.full_viewing_key.clone())?, keys.view_key);
+
+        let watch_only = ProofManager::from_full_viewing_key(keys.full_viewing_key)?;
+        assert!(watch_only.is_watch_only());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_only_mode() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let watch_only = ProofManager::from_full_viewing_key(keys.full_viewing_key.clone())?;
+
+        // Same addresses as the spending wallet
+        assert_eq!(
+            watch_only.watch_address(1)?.transmission_key,
+            manager.generate_address(keys.spend_key, 1)?.transmission_key,
+        );
+
+        // Verifying is fine, signing is not
+        let signed = signed_test_note(&manager)?;
+        assert!(watch_only.verify_signed_note(signed)?);
+        let note = signed_test_note(&manager)?.note;
+        assert!(matches!(
+            watch_only.sign_note(DEBTOR_SEED.to_string(), 0, note),
+            Err(ProofError::WatchOnly)
+        ));
+
+        // A spending instance has no viewing key of its own
+        assert!(matches!(manager.watch_address(1), Err(ProofError::MissingViewingKey)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fresh_randomizer_per_signature() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let note = signed_test_note(&manager)?.note;
+        let first = manager.sign_note_for_holder(DEBTOR_SEED.to_string(), 0, note.clone())?;
+        let second = manager.sign_note_for_holder(DEBTOR_SEED.to_string(), 0, note)?;
+        assert_ne!(first.signed_note.verification_key, second.signed_note.verification_key);
+
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let ak = manager.spend_verification_key(keys.spend_key)?;
+        assert!(manager.verify_randomized_key(
+            ak.clone(),
+            first.spend_auth_randomizer,
+            first.signed_note.verification_key.clone(),
+        )?);
+        assert!(!manager.verify_randomized_key(
+            ak,
+            second.spend_auth_randomizer,
+            first.signed_note.verification_key,
+        )?);
+
+        Ok(())
+    }
+
+    struct UsedAccounts(Vec<String>);
+
+    impl AccountActivity for UsedAccounts {
+        fn is_account_used(&self, _bip44_account: u32, full_viewing_key: String) -> bool {
+            self.0.contains(&full_viewing_key)
+        }
+    }
+
+    #[test]
+    fn test_account_derivation_and_discovery() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let first = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let second = manager.generate_keys(DEBTOR_SEED.to_string(), 1)?;
+        assert_ne!(first.spend_key, second.spend_key);
+
+        // Signing from another account of the same seed is not the debtor
+        let note = signed_test_note(&manager)?.note;
+        assert!(matches!(
+            manager.sign_note(DEBTOR_SEED.to_string(), 1, note),
+            Err(ProofError::NotDebtor)
+        ));
+
+        let used = UsedAccounts(vec![first.full_viewing_key, second.full_viewing_key]);
+        assert_eq!(manager.discover_accounts(DEBTOR_SEED.to_string(), Box::new(used))?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seed_phrase_generation_and_validation() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        for word_count in [12u8, 24] {
+            let seed = manager.generate_seed_phrase(word_count)?;
+            assert_eq!(seed.split_whitespace().count(), word_count as usize);
+            manager.validate_seed_phrase(seed)?;
+        }
+        assert!(matches!(manager.generate_seed_phrase(15), Err(ProofError::InvalidSeedLength(15))));
+
+        assert!(matches!(
+            manager.validate_seed_phrase("abandon abandon abandon".to_string()),
+            Err(ProofError::InvalidSeedLength(3))
+        ));
+        assert!(matches!(
+            manager.validate_seed_phrase(CREDITOR_SEED.replace("about", "pocketlib")),
+            Err(ProofError::UnknownSeedWord { index: 11 })
+        ));
+        assert!(matches!(
+            manager.validate_seed_phrase(CREDITOR_SEED.replace("about", "abandon")),
+            Err(ProofError::InvalidSeedChecksum)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keystore_signing() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keystore = manager.create_keystore(DEBTOR_SEED.to_string(), 0, "hunter2".to_string())?;
+        assert!(matches!(
+            manager.unlock(keystore.clone(), "hunter3".to_string()),
+            Err(ProofError::WrongPassword)
+        ));
+
+        let unlocked = manager.unlock(keystore, "hunter2".to_string())?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        assert_eq!(unlocked.full_viewing_key(), keys.full_viewing_key);
+
+        let note = signed_test_note(&manager)?.note;
+        let signed = manager.sign_note_with_keystore(unlocked, note)?;
+        assert!(manager.verify_signed_note(signed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_store_signing() -> Result<(), ProofError> {
+        let manager = ProofManager::with_memory_key_store();
+        load_test_parameters(&manager);
+        manager.store_key("main".to_string(), DEBTOR_SEED.to_string(), 0)?;
+        assert_eq!(manager.list_keys()?, vec!["main".to_string()]);
+
+        let note = signed_test_note(&manager)?.note;
+        let signed = manager.sign_note_with_stored_key("main".to_string(), note)?;
+        assert!(manager.verify_signed_note(signed)?);
+
+        manager.delete_key("main".to_string())?;
+        let note = signed_test_note(&manager)?.note;
+        assert!(matches!(
+            manager.sign_note_with_stored_key("main".to_string(), note),
+            Err(ProofError::KeyNotFound(_))
+        ));
+        assert!(matches!(test_manager()?.list_keys(), Err(ProofError::MissingKeyStore)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seed_backup_roundtrip() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let shares = manager.split_seed(DEBTOR_SEED.to_string(), 2, 3)?;
+
+        let restored = manager.combine_shares(vec![shares[0].clone(), shares[2].clone()])?;
+        assert_eq!(restored, DEBTOR_SEED);
+        assert!(matches!(
+            manager.combine_shares(vec![shares[1].clone()]),
+            Err(ProofError::InvalidShare(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_address_strings() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let address = manager.generate_address(keys.spend_key, 1)?;
+
+        let encoded = manager.address_to_string(address.clone())?;
+        assert!(encoded.starts_with("penumbra1"));
+        let decoded = manager.address_from_string(encoded.clone())?;
+        assert_eq!(decoded.transmission_key, address.transmission_key);
+        assert_eq!(decoded.diversifier, address.diversifier);
+        assert_eq!(decoded.clue_key, address.clue_key);
+
+        let short = manager.address_short_form(address)?;
+        assert!(short.len() < encoded.len());
+        assert!(matches!(
+            manager.address_from_string(short),
+            Err(ProofError::InvalidAddress)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_address_index_lookup() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let address = manager.generate_address(keys.spend_key, 7)?;
+
+        let index = manager
+            .address_index_of(keys.full_viewing_key.clone(), address.clone())?
+            .expect("our own address");
+        assert_eq!(index.address_index, 7);
+
+        let creditor_keys = manager.generate_keys(CREDITOR_SEED.to_string(), 0)?;
+        let theirs = manager.generate_address(creditor_keys.spend_key, 7)?;
+        assert!(manager.address_index_of(keys.full_viewing_key, theirs)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ephemeral_and_contact_addresses() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+
+        // Ephemeral addresses differ every time but still belong to the account
+        let first = manager.generate_ephemeral_address(keys.spend_key.clone(), 3)?;
+        let second = manager.generate_ephemeral_address(keys.spend_key, 3)?;
+        assert_ne!(first.diversifier, second.diversifier);
+        let index = manager
+            .address_index_of(keys.full_viewing_key.clone(), first)?
+            .expect("our own address");
+        assert_eq!(index.address_index, 3);
+
+        let book = ContactAddressBook::new(keys.full_viewing_key.clone())?;
+        let alice = book.address_for("alice".to_string())?;
+        let bob = book.address_for("bob".to_string())?;
+        assert_ne!(alice.diversifier, bob.diversifier);
+
+        let restored = ContactAddressBook::from_json(keys.full_viewing_key.clone(), Some(book.to_json()?))?;
+        assert_eq!(restored.address_for("alice".to_string())?.diversifier, alice.diversifier);
+        assert_eq!(restored.contact_of(2), Some("bob".to_string()));
+
+        // State whose next index would hand bob's address to a new contact
+        let reused = r#"{"next_index":2,"contacts":{"alice":1,"bob":2}}"#.to_string();
+        assert!(matches!(
+            ContactAddressBook::from_json(keys.full_viewing_key, Some(reused)),
+            Err(ProofError::InvalidAddressBook(_))
+        ));
+
+        Ok(())
+    }
 
-//         // Create and sign note
-//         let note = manager.create_note(
-//             debtor_address,
-//             creditor_address,
-//             30u64,
-//             1u64,
-//         )?;
+    #[test]
+    fn test_payment_request_flow() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let creditor_keys = manager.generate_keys(CREDITOR_SEED.to_string(), 0)?;
+        let creditor_address = manager.generate_address(creditor_keys.spend_key, 1)?;
+        let debtor_keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let debtor_address = manager.generate_address(debtor_keys.spend_key, 1)?;
+
+        let request = PaymentRequestData {
+            creditor_address: creditor_address.clone(),
+            amount: 30,
+            asset_id: 1,
+            memo: "lunch".to_string(),
+            expires_at: 0,
+        };
+        assert!(matches!(
+            manager.sign_payment_request(DEBTOR_SEED.to_string(), 0, request.clone()),
+            Err(ProofError::NotCreditor)
+        ));
+
+        let signed = manager.sign_payment_request(CREDITOR_SEED.to_string(), 0, request)?;
+        let uri = manager.payment_request_uri(signed)?;
+        assert!(uri.starts_with("pocketlib:"));
+
+        let parsed = manager.parse_payment_request(uri.clone())?;
+        assert_eq!(parsed.memo, "lunch");
+
+        // Only a signed request can be paid
+        let mut tampered = uri.clone();
+        tampered.pop();
+        assert!(manager.create_note_from_request(debtor_address.clone(), tampered).is_err());
+
+        let note = manager.create_note_from_request(debtor_address, uri)?;
+        assert_eq!(note.amount, 30);
+        assert_eq!(note.creditor_address.transmission_key, creditor_address.transmission_key);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multipart_transfer() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let signed = signed_test_note(&manager)?;
+        let blob = [signed.signature, signed.authorization_proof].concat();
+
+        let encoder = MultipartEncoder::new(blob.clone(), 32);
+        let decoder = MultipartDecoder::new();
+        // Drop every other part, as a camera would miss frames
+        let mut parts = 0;
+        loop {
+            let part = encoder.next_part();
+            parts += 1;
+            if parts % 2 == 0 && decoder.receive(part)? {
+                break;
+            }
+            assert!(parts < 1000, "decoder did not converge");
+        }
+        assert_eq!(decoder.message(), Some(blob));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_notes() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let keys = manager.generate_keys(DEBTOR_SEED.to_string(), 0)?;
+        let debtor_address = manager.generate_address(keys.spend_key, 1)?;
+        let creditor_keys = manager.generate_keys(CREDITOR_SEED.to_string(), 0)?;
+        let creditor_address = manager.generate_address(creditor_keys.spend_key, 1)?;
+
+        let note = |amount, counter| manager.create_deterministic_note(
+            DEBTOR_SEED.to_string(), 0, debtor_address.clone(), creditor_address.clone(), amount, 1, counter,
+        );
+        assert_eq!(note(30, 0)?.commitment, note(30, 0)?.commitment);
+        assert_ne!(note(30, 0)?.commitment, note(30, 1)?.commitment);
+        // A different value never shares an rseed, even under the same counter
+        assert_ne!(note(30, 0)?.rseed, note(31, 0)?.rseed);
+
+        assert!(matches!(
+            manager.create_deterministic_note(
+                CREDITOR_SEED.to_string(), 0, debtor_address.clone(), creditor_address.clone(), 30, 1, 0,
+            ),
+            Err(ProofError::NotDebtor)
+        ));
+
+        Ok(())
+    }
 
-//         let spendkey = keys.spend_key.
+    #[test]
+    fn test_note_export_import() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let note = signed_test_note(&manager)?.note;
 
+        let json = manager.export_note_json(note.clone())?;
+        let from_json = manager.import_note_json(json)?;
+        assert_eq!(from_json.commitment, note.commitment);
+        assert_eq!(from_json.rseed, note.rseed);
 
-//         let signed = manager.sign_note(, note)?;
+        let bytes = manager.export_note_bytes(note.clone())?;
+        let from_bytes = manager.import_note_bytes(bytes)?;
+        assert_eq!(from_bytes.commitment, note.commitment);
+        assert_eq!(from_bytes.amount, note.amount);
 
-//         // Verify signature
-//         assert!(manager.verify_signature(
-//             signed.verification_key,
-//             signed.note.commitment,
-//             signed.signature,
-//         )?);
+        Ok(())
+    }
 
-//         Ok(())
-//     }
-// }
\ No newline at end of file
+    #[test]
+    fn test_commitment_reverification() -> Result<(), ProofError> {
+        let manager = test_manager()?;
+        let signed = signed_test_note(&manager)?;
+        let note = signed.note.clone();
+        manager.verify_note_commitment(note.clone(), note.commitment.clone())?;
+
+        // Changing a field breaks the opening
+        let mut inflated = note.clone();
+        inflated.amount = 3_000;
+        assert!(matches!(
+            manager.verify_note_commitment(inflated.clone(), note.commitment.clone()),
+            Err(ProofError::CommitmentMismatch(MismatchedCommitment::Expected))
+        ));
+        assert!(matches!(
+            manager.sign_note(DEBTOR_SEED.to_string(), 0, inflated),
+            Err(ProofError::CommitmentMismatch(MismatchedCommitment::Expected))
+        ));
+
+        // Fields that do not parse report why, not a mismatch
+        let mut truncated = note.clone();
+        truncated.rseed.pop();
+        assert!(matches!(
+            manager.verify_note_commitment(truncated, note.commitment.clone()),
+            Err(ProofError::NoteError(_))
+        ));
+        let mut self_paying = note.clone();
+        self_paying.creditor_address = self_paying.debtor_address.clone();
+        assert!(matches!(
+            manager.verify_note_commitment(self_paying, note.commitment.clone()),
+            Err(ProofError::NoteError(_))
+        ));
+
+        // A record carrying someone else's commitment
+        let other = signed_test_note(&manager)?.note;
+        let mut swapped = note;
+        swapped.commitment = other.commitment;
+        let commitment = manager.export_note_bytes(swapped.clone())
+            .and_then(|bytes| manager.import_note_bytes(bytes))?
+            .commitment;
+        assert!(matches!(
+            manager.verify_note_commitment(swapped, commitment),
+            Err(ProofError::CommitmentMismatch(MismatchedCommitment::Recorded))
+        ));
+
+        // Signature verification rejects notes whose fields were edited
+        let mut tampered = signed;
+        tampered.note.amount = 3_000;
+        assert!(!manager.verify_signed_note(tampered)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use decaf377_ka as ka;
+use penumbra_keys::keys::{IncomingViewingKey, OutgoingViewingKey};
+use penumbra_keys::symmetric::{PayloadKey, PayloadKind};
+use penumbra_shielded_pool::note::Error;
+use penumbra_tct::StateCommitment;
+
+use crate::note::Note;
+
+/// Length of the ephemeral public key that prefixes every note ciphertext.
+pub const EPK_LEN_BYTES: usize = 32;
+
+impl Note {
+    /// The ephemeral public key used to encrypt this note to its creditor.
+    pub fn ephemeral_public_key(&self) -> ka::Public {
+        let esk = self.rseed.derive_esk();
+        esk.diversified_public(&self.creditor.diversifier().diversified_generator())
+    }
+
+    /// Encrypts the note to the creditor's transmission key.
+    ///
+    /// The ephemeral secret is derived from the note's `Rseed`, so the
+    /// ciphertext is `epk || payload` and needs no other side channel. The
+    /// payload is the fixed-length [`Note::to_bytes`] encoding, so every note
+    /// ciphertext has the same length whatever its value.
+    pub fn encrypt(&self) -> Result<Vec<u8>, Error> {
+        let esk = self.rseed.derive_esk();
+        let epk = self.ephemeral_public_key();
+        let shared_secret = esk
+            .key_agreement_with(self.creditor.transmission_key())
+            .map_err(|_| Error::InvalidTransmissionKey)?;
+        let key = PayloadKey::derive(&shared_secret, &epk);
+
+        let mut ciphertext = epk.0.to_vec();
+        ciphertext.extend(key.encrypt(self.to_bytes(), PayloadKind::Note));
+        Ok(ciphertext)
+    }
+
+    /// Trial-decrypts a note ciphertext with the creditor's incoming viewing key.
+    pub fn decrypt(ciphertext: &[u8], ivk: &IncomingViewingKey) -> Result<Note, Error> {
+        if ciphertext.len() < EPK_LEN_BYTES {
+            return Err(Error::DecryptionError);
+        }
+        let (epk, payload) = ciphertext.split_at(EPK_LEN_BYTES);
+        let epk = ka::Public(epk.try_into().map_err(|_| Error::DecryptionError)?);
+
+        let shared_secret = ivk
+            .key_agreement_with(&epk)
+            .map_err(|_| Error::DecryptionError)?;
+        let key = PayloadKey::derive(&shared_secret, &epk);
+        let plaintext = key
+            .decrypt(payload.to_vec(), PayloadKind::Note)
+            .map_err(|_| Error::DecryptionError)?;
+
+        let note = Note::from_bytes(&plaintext)?;
+
+        // The note must really be ours and match the key it was sent with
+        if !ivk.views_address(&note.creditor) || note.ephemeral_public_key() != epk {
+            return Err(Error::DecryptionError);
+        }
+
+        Ok(note)
+    }
+
+    /// Encrypts the note under the debtor's outgoing viewing key.
+    ///
+    /// This is the aux ciphertext: it lets a debtor who restores from seed
+    /// recover every note they issued.
+    pub fn encrypt_outgoing(&self, ovk: &OutgoingViewingKey) -> Result<Vec<u8>, Error> {
+        let cipher = outgoing_cipher(ovk, &self.commit());
+
+        cipher
+            .encrypt(Nonce::from_slice(&[0u8; 12]), self.to_bytes().as_slice())
+            .map_err(|_| Error::DecryptionError)
+    }
+
+    /// Decrypts an aux ciphertext for the note committed to by `commitment`.
+    pub fn decrypt_outgoing(
+        ciphertext: &[u8],
+        ovk: &OutgoingViewingKey,
+        commitment: &StateCommitment,
+    ) -> Result<Note, Error> {
+        let cipher = outgoing_cipher(ovk, commitment);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&[0u8; 12]), ciphertext)
+            .map_err(|_| Error::DecryptionError)?;
+
+        let note = Note::from_bytes(&plaintext)?;
+        if note.commit() != *commitment {
+            return Err(Error::InvalidNoteCommitment);
+        }
+
+        Ok(note)
+    }
+}
+
+/// Derives the aux ciphertext key from the debtor's OVK and the note commitment.
+///
+/// The key is unique per note, which is what makes the fixed nonce safe.
+fn outgoing_cipher(ovk: &OutgoingViewingKey, commitment: &StateCommitment) -> ChaCha20Poly1305 {
+    let key = blake2b_simd::Params::new()
+        .personal(b"pocketlib_AuxKey")
+        .hash_length(32)
+        .to_state()
+        .update(&ovk.to_bytes())
+        .update(&commitment.0.to_bytes())
+        .finalize();
+
+    ChaCha20Poly1305::new(Key::from_slice(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::note::test_note;
+    use decaf377::Fq;
+    use penumbra_asset::{asset, Value};
+    use penumbra_keys::keys::{Bip44Path, SeedPhrase, SpendKey};
+    use penumbra_keys::Address;
+    use penumbra_shielded_pool::Rseed;
+    use rand::thread_rng;
+
+    #[test]
+    fn only_the_creditor_can_decrypt() {
+        let mut rng = thread_rng();
+        let creditor_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let creditor_ivk = creditor_key.full_viewing_key().incoming();
+        let (creditor, _) = creditor_ivk.payment_address(3u32.into());
+
+        let note = test_note(Address::dummy(&mut rng), creditor);
+
+        let ciphertext = note.encrypt().unwrap();
+        assert_eq!(Note::decrypt(&ciphertext, creditor_ivk).unwrap(), note);
+
+        let stranger_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        assert!(Note::decrypt(&ciphertext, stranger_key.full_viewing_key().incoming()).is_err());
+    }
+
+    #[test]
+    fn ciphertext_length_does_not_depend_on_the_value() {
+        let mut rng = thread_rng();
+        let debtor_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let ovk = debtor_key.full_viewing_key().outgoing();
+        let creditor = Address::dummy(&mut rng);
+
+        let with_amount = |amount: u64| {
+            Note::from_parts(
+                Address::dummy(&mut thread_rng()),
+                creditor.clone(),
+                Value {
+                    amount: amount.into(),
+                    asset_id: asset::Id(Fq::from(1u64)),
+                },
+                Rseed::generate(&mut thread_rng()),
+            )
+            .unwrap()
+        };
+        let (zero, max) = (with_amount(0), with_amount(u64::MAX));
+
+        assert_eq!(zero.encrypt().unwrap().len(), max.encrypt().unwrap().len());
+        assert_eq!(
+            zero.encrypt_outgoing(ovk).unwrap().len(),
+            max.encrypt_outgoing(ovk).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn debtor_recovers_outgoing_note() {
+        let mut rng = thread_rng();
+        let debtor_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let ovk = debtor_key.full_viewing_key().outgoing();
+        let (debtor, _) = debtor_key.full_viewing_key().incoming().payment_address(0u32.into());
+
+        let note = test_note(debtor, Address::dummy(&mut rng));
+
+        let aux = note.encrypt_outgoing(ovk).unwrap();
+        assert_eq!(Note::decrypt_outgoing(&aux, ovk, &note.commit()).unwrap(), note);
+    }
+}
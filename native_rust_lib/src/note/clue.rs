@@ -0,0 +1,49 @@
+use decaf377_fmd as fmd;
+use rand::rngs::OsRng;
+
+use crate::note::Note;
+
+impl Note {
+    /// Creates a fuzzy message detection clue for the creditor.
+    pub fn create_clue(&self, precision_bits: u8) -> anyhow::Result<fmd::Clue> {
+        self::create_clue(self.creditor.clue_key(), precision_bits)
+    }
+}
+
+/// Creates a fuzzy message detection clue for the holder of `clue_key`.
+///
+/// A detection key holder learns that the note *may* be for them: unrelated
+/// notes match with probability `2^-precision_bits`.
+pub fn create_clue(clue_key: &fmd::ClueKey, precision_bits: u8) -> anyhow::Result<fmd::Clue> {
+    let precision = fmd::Precision::new(precision_bits)?;
+    let clue = clue_key.expand()?.create_clue(precision, OsRng)?;
+    Ok(clue)
+}
+
+/// Checks a clue against a detection key, as a relay would.
+pub fn detect(detection_key: &fmd::DetectionKey, clue: &fmd::Clue) -> bool {
+    detection_key.examine(clue)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::note::test_note;
+    use penumbra_keys::keys::{Bip44Path, SeedPhrase, SpendKey};
+    use penumbra_keys::Address;
+    use rand::thread_rng;
+
+    #[test]
+    fn creditor_detects_own_clue() {
+        let mut rng = thread_rng();
+        let spend_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let (creditor, detection_key) =
+            spend_key.full_viewing_key().incoming().payment_address(1u32.into());
+
+        let note = test_note(Address::dummy(&mut rng), creditor);
+
+        let clue = note.create_clue(8).unwrap();
+        assert!(detect(&detection_key, &clue));
+    }
+}
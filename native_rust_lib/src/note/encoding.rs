@@ -0,0 +1,97 @@
+use decaf377::Fq;
+use decaf377_fmd as fmd;
+use decaf377_ka as ka;
+use penumbra_asset::{asset, Value};
+use penumbra_keys::keys::Diversifier;
+use penumbra_keys::Address;
+use penumbra_num::Amount;
+use penumbra_shielded_pool::note::Error;
+use penumbra_shielded_pool::Rseed;
+
+use crate::note::Note;
+
+/// The only binary note encoding version this library writes.
+pub const VERSION: u8 = 1;
+
+/// diversifier || transmission key || clue key
+pub(crate) const ADDRESS_LEN_BYTES: usize = 16 + 32 + 32;
+/// version || debtor || creditor || amount || asset id || rseed
+pub const NOTE_LEN_BYTES: usize = 1 + 2 * ADDRESS_LEN_BYTES + 16 + 32 + 32;
+
+impl Note {
+    /// The canonical binary encoding of the full note opening.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(NOTE_LEN_BYTES);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&address_to_bytes(&self.debtor));
+        bytes.extend_from_slice(&address_to_bytes(&self.creditor));
+        bytes.extend_from_slice(&self.value.amount.value().to_le_bytes());
+        bytes.extend_from_slice(&self.value.asset_id.0.to_bytes());
+        bytes.extend_from_slice(&self.rseed.0);
+        bytes
+    }
+
+    /// Decodes a note produced by [`Note::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Note, Error> {
+        if bytes.len() != NOTE_LEN_BYTES || bytes[0] != VERSION {
+            return Err(Error::NoteDeserializationError);
+        }
+
+        let (debtor, rest) = bytes[1..].split_at(ADDRESS_LEN_BYTES);
+        let (creditor, rest) = rest.split_at(ADDRESS_LEN_BYTES);
+        let debtor = address_from_bytes(debtor.try_into().expect("address length"))
+            .ok_or(Error::NoteDeserializationError)?;
+        let creditor = address_from_bytes(creditor.try_into().expect("address length"))
+            .ok_or(Error::NoteDeserializationError)?;
+
+        let amount = u128::from_le_bytes(rest[..16].try_into().expect("16 bytes"));
+        let asset_id = Fq::from_bytes_checked(&rest[16..48].try_into().expect("32 bytes"))
+            .map_err(|_| Error::NoteDeserializationError)?;
+        let rseed = Rseed(rest[48..].try_into().expect("32 bytes"));
+
+        Note::from_parts(
+            debtor,
+            creditor,
+            Value {
+                amount: Amount::from(amount),
+                asset_id: asset::Id(asset_id),
+            },
+            rseed,
+        )
+    }
+}
+
+/// The binary address layout shared by notes and payment requests.
+pub(crate) fn address_to_bytes(address: &Address) -> [u8; ADDRESS_LEN_BYTES] {
+    let mut bytes = [0u8; ADDRESS_LEN_BYTES];
+    bytes[..16].copy_from_slice(&address.diversifier().0);
+    bytes[16..48].copy_from_slice(&address.transmission_key().0);
+    bytes[48..].copy_from_slice(&address.clue_key().0);
+    bytes
+}
+
+/// Decodes [`address_to_bytes`]; `None` if the keys are not a valid address.
+pub(crate) fn address_from_bytes(bytes: &[u8; ADDRESS_LEN_BYTES]) -> Option<Address> {
+    Address::from_components(
+        Diversifier(bytes[..16].try_into().expect("16 bytes")),
+        ka::Public(bytes[16..48].try_into().expect("32 bytes")),
+        fmd::ClueKey(bytes[48..].try_into().expect("32 bytes")),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn binary_roundtrip() {
+        let mut rng = thread_rng();
+        let note = crate::note::test_note(Address::dummy(&mut rng), Address::dummy(&mut rng));
+
+        let bytes = note.to_bytes();
+        assert_eq!(bytes.len(), NOTE_LEN_BYTES);
+        assert_eq!(Note::from_bytes(&bytes).unwrap(), note);
+        assert!(Note::from_bytes(&bytes[1..]).is_err());
+    }
+}
@@ -5,6 +5,7 @@ use decaf377_fmd as fmd;
 use decaf377_ka as ka;
 use once_cell::sync::Lazy;
 use penumbra_asset::{asset, Value};
+use penumbra_keys::keys::SpendKey;
 use penumbra_keys::Address;
 use penumbra_num::Amount;
 use penumbra_shielded_pool::note::Error;
@@ -13,12 +14,40 @@ use penumbra_tct::StateCommitment;
 use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeStruct;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
 
+pub mod ciphertext;
+pub mod clue;
+pub mod encoding;
+pub mod r1cs;
+pub mod scan;
 
 pub(crate) static NOTECOMMIT_DOMAIN_SEP: Lazy<Fq> = Lazy::new(|| {
     Fq::from_le_bytes_mod_order(blake2b_simd::blake2b(b"penumbra.notecommit").as_bytes())
 });
 
+pub(crate) static NOTECOMMIT_V2_DOMAIN_SEP: Lazy<Fq> = Lazy::new(|| {
+    Fq::from_le_bytes_mod_order(blake2b_simd::blake2b(b"pocketlib.notecommit.v2").as_bytes())
+});
+
+pub(crate) static CREDITOR_DOMAIN_SEP: Lazy<Fq> = Lazy::new(|| {
+    Fq::from_le_bytes_mod_order(blake2b_simd::blake2b(b"pocketlib.notecommit.creditor").as_bytes())
+});
+
+/// The note commitment schemes this crate knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitmentVersion {
+    /// Binds only the s-component of the creditor's transmission key.
+    V1,
+    /// Binds the creditor's diversified generator, transmission key and clue key.
+    V2,
+}
+
+impl CommitmentVersion {
+    /// The version used for every newly created note.
+    pub const CURRENT: CommitmentVersion = CommitmentVersion::V2;
+}
+
 /// A plaintext Penumbra note.
 #[derive(Clone, PartialEq, Eq)]
 pub struct Note {
@@ -96,15 +125,34 @@ impl Note {
         self.debtor.clue_key()
     }
 
+    /// Commits to the note with the current commitment version.
     pub fn commit(&self) -> StateCommitment {
-        self::commitment(
-            self.note_blinding(),
-            self.value,
-            self.diversified_generator(),
-            self.transmission_key_s,
-            self.debtor.clue_key(),
-            self.creditor_transmission_key_s,
-        )
+        self.commit_with_version(CommitmentVersion::CURRENT)
+    }
+
+    pub fn commit_with_version(&self, version: CommitmentVersion) -> StateCommitment {
+        match version {
+            CommitmentVersion::V1 => self::commitment(
+                self.note_blinding(),
+                self.value,
+                self.diversified_generator(),
+                self.transmission_key_s,
+                self.debtor.clue_key(),
+                self.creditor_transmission_key_s,
+            ),
+            CommitmentVersion::V2 => self::commitment_v2(
+                self.note_blinding(),
+                self.value,
+                self.diversified_generator(),
+                self.transmission_key_s,
+                self.debtor.clue_key(),
+                self::creditor_commitment(
+                    self.creditor.diversifier().diversified_generator(),
+                    self.creditor_transmission_key_s,
+                    self.creditor.clue_key(),
+                ),
+            ),
+        }
     }
 
     pub fn asset_id(&self) -> asset::Id {
@@ -115,8 +163,8 @@ impl Note {
         self.value.amount
     }
 
-    pub fn rseed(&self) -> Rseed {
-        self.rseed
+    pub fn rseed(&self) -> &Rseed {
+        &self.rseed
     }
 }
 
@@ -126,11 +174,57 @@ impl std::fmt::Debug for Note {
             .field("value", &self.value)
             .field("debtor", &self.debtor())
             .field("creditor", &self.creditor())
-            .field("rseed", &hex::encode(self.rseed.to_bytes()))
+            .field("rseed", &"<redacted>")
             .finish()
     }
 }
 
+/// The rseed determines the note blinding and the encryption secret, so wipe it.
+///
+/// `Rseed` is `Copy`: this wipes the note's own copy only. Callers that hold
+/// an rseed outside a `Note`, or copy its bytes out, must wipe those copies.
+impl Drop for Note {
+    fn drop(&mut self) {
+        self.rseed.0.zeroize();
+    }
+}
+
+/// Derives the rseed of the `counter`-th note of `value` from `debtor` to `creditor`.
+///
+/// Keyed by the debtor's spend key, so only the debtor can rederive it, and
+/// a wallet restored from seed can rebuild every note it issued.
+///
+/// The counter must never repeat for the same debtor and creditor addresses:
+/// the same inputs give the same rseed, so two notes would share their
+/// blinding and encryption secret. Callers persist the last counter used.
+pub fn derive_rseed(
+    spend_key: &SpendKey,
+    debtor: &Address,
+    creditor: &Address,
+    value: Value,
+    counter: u64,
+) -> Rseed {
+    let spend_key_bytes = zeroize::Zeroizing::new(spend_key.to_bytes().0);
+    let mut state = blake2b_simd::Params::new()
+        .personal(b"pocketlib_Rseed_")
+        .key(spend_key_bytes.as_slice())
+        .hash_length(32)
+        .to_state();
+    for address in [debtor, creditor] {
+        state
+            .update(&address.diversifier().0)
+            .update(&address.transmission_key().0)
+            .update(&address.clue_key().0);
+    }
+    let hash = state
+        .update(&value.amount.value().to_le_bytes())
+        .update(&value.asset_id.0.to_bytes())
+        .update(&counter.to_le_bytes())
+        .finalize();
+
+    Rseed(hash.as_bytes().try_into().expect("hash is 32 bytes"))
+}
+
 pub fn commitment(
     note_blinding: Fq,
     value: Value,
@@ -155,6 +249,49 @@ pub fn commitment(
     StateCommitment(commit)
 }
 
+/// Hashes every component of the creditor address into one field element.
+pub fn creditor_commitment(
+    diversified_generator: decaf377::Element,
+    transmission_key_s: Fq,
+    clue_key: &fmd::ClueKey,
+) -> Fq {
+    poseidon377::hash_3(
+        &CREDITOR_DOMAIN_SEP,
+        (
+            diversified_generator.vartime_compress_to_field(),
+            transmission_key_s,
+            Fq::from_le_bytes_mod_order(&clue_key.0[..]),
+        ),
+    )
+}
+
+/// The v2 note commitment, binding the full debtor and creditor addresses.
+///
+/// `creditor` is the output of [`creditor_commitment`].
+pub fn commitment_v2(
+    note_blinding: Fq,
+    value: Value,
+    diversified_generator: decaf377::Element,
+    transmission_key_s: Fq,
+    clue_key: &fmd::ClueKey,
+    creditor: Fq,
+) -> StateCommitment {
+    let commit = poseidon377::hash_7(
+        &NOTECOMMIT_V2_DOMAIN_SEP,
+        (
+            note_blinding,
+            value.amount.into(),
+            value.asset_id.0,
+            diversified_generator.vartime_compress_to_field(),
+            transmission_key_s,
+            Fq::from_le_bytes_mod_order(&clue_key.0[..]),
+            creditor,
+        ),
+    );
+
+    StateCommitment(commit)
+}
+
 // Implement Serialize for Note
 impl Serialize for Note {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -164,7 +301,7 @@ impl Serialize for Note {
         // Use a map to serialize fields
         let mut state = serializer.serialize_struct("Note", 4)?;
         state.serialize_field("value", &self.value)?;
-        state.serialize_field("rseed", &self.rseed.to_bytes())?;
+        state.serialize_field("rseed", &self.rseed.0)?;
         state.serialize_field("debtor", &self.debtor)?;
         state.serialize_field("creditor", &self.creditor)?;
         state.end()
@@ -246,10 +383,26 @@ impl<'de> Deserialize<'de> for Note {
     }
 }
 
+/// A note of 10 units of asset 1 with a fresh rseed, for tests.
+#[cfg(test)]
+pub(crate) fn test_note(debtor: Address, creditor: Address) -> Note {
+    Note::from_parts(
+        debtor,
+        creditor,
+        Value {
+            amount: 10u64.into(),
+            asset_id: asset::Id(Fq::from(1u64)),
+        },
+        Rseed::generate(&mut rand::thread_rng()),
+    )
+    .expect("hardcoded note")
+}
+
 #[cfg(test)]
 mod test {
-    use crate::note::Note;
+    use crate::note::{test_note, CommitmentVersion, Note};
     use decaf377::Fq;
+    use decaf377_fmd as fmd;
     use penumbra_asset::asset::Id;
     use penumbra_asset::Value;
     use penumbra_keys::Address;
@@ -274,4 +427,42 @@ mod test {
         let note_deserialized = serde_json::from_slice(&note_serialized).unwrap();
         assert_eq!(note, note_deserialized);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_debug_redacts_rseed() {
+        let mut rng = thread_rng();
+        let note = test_note(Address::dummy(&mut rng), Address::dummy(&mut rng));
+
+        let debug = format!("{note:?}");
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(&hex::encode(note.rseed().0)));
+    }
+
+    #[test]
+    fn test_v2_binds_creditor_clue_key() {
+        let mut rng = thread_rng();
+        let debtor = Address::dummy(&mut rng);
+        let creditor = Address::dummy(&mut rng);
+        let other_creditor = Address::from_components(
+            *creditor.diversifier(),
+            *creditor.transmission_key(),
+            fmd::ClueKey([7u8; 32]),
+        )
+        .expect("valid components");
+        let value = Value {
+            amount: 10u64.into(),
+            asset_id: Id(Fq::from(1u64)),
+        };
+        let rseed = Rseed::generate(&mut rng);
+
+        let note = Note::from_parts(debtor.clone(), creditor, value, rseed).unwrap();
+        let other = Note::from_parts(debtor, other_creditor, value, rseed).unwrap();
+
+        // v1 cannot tell the two creditors apart, v2 can
+        assert_eq!(
+            note.commit_with_version(CommitmentVersion::V1),
+            other.commit_with_version(CommitmentVersion::V1)
+        );
+        assert_ne!(note.commit(), other.commit());
+    }
+}
@@ -9,7 +9,7 @@ use penumbra_asset::ValueVar;
 use penumbra_keys::address::AddressVar;
 use penumbra_tct::r1cs::StateCommitmentVar;
 
-use super::NOTECOMMIT_DOMAIN_SEP;
+use super::{CommitmentVersion, CREDITOR_DOMAIN_SEP, NOTECOMMIT_DOMAIN_SEP, NOTECOMMIT_V2_DOMAIN_SEP};
 use crate::note::Note;
 
 pub struct NoteVar {
@@ -94,7 +94,22 @@ impl ToConstraintField<Fq> for Note {
 // since we do not have the rseed in-circuit.
 
 impl NoteVar {
+    /// Commits to the note with the current commitment version.
     pub fn commit(&self) -> Result<StateCommitmentVar, SynthesisError> {
+        self.commit_with_version(CommitmentVersion::CURRENT)
+    }
+
+    pub fn commit_with_version(
+        &self,
+        version: CommitmentVersion,
+    ) -> Result<StateCommitmentVar, SynthesisError> {
+        match version {
+            CommitmentVersion::V1 => self.commit_v1(),
+            CommitmentVersion::V2 => self.commit_v2(),
+        }
+    }
+
+    fn commit_v1(&self) -> Result<StateCommitmentVar, SynthesisError> {
         let cs = self.amount().cs();
         let domain_sep = FqVar::new_constant(cs.clone(), *NOTECOMMIT_DOMAIN_SEP)?;
         let compressed_g_d = self.debtor.diversified_generator().compress_to_field()?;
@@ -115,4 +130,60 @@ impl NoteVar {
 
         Ok(StateCommitmentVar { inner: commitment })
     }
+
+    fn commit_v2(&self) -> Result<StateCommitmentVar, SynthesisError> {
+        let cs = self.amount().cs();
+        let domain_sep = FqVar::new_constant(cs.clone(), *NOTECOMMIT_V2_DOMAIN_SEP)?;
+        let creditor_domain_sep = FqVar::new_constant(cs.clone(), *CREDITOR_DOMAIN_SEP)?;
+
+        let creditor = poseidon377::r1cs::hash_3(
+            cs.clone(),
+            &creditor_domain_sep,
+            (
+                self.creditor.diversified_generator().compress_to_field()?,
+                self.creditor.transmission_key().compress_to_field()?,
+                self.creditor.clue_key(),
+            ),
+        )?;
+
+        let commitment = poseidon377::r1cs::hash_7(
+            cs,
+            &domain_sep,
+            (
+                self.note_blinding.clone(),
+                self.value.amount(),
+                self.value.asset_id(),
+                self.debtor.diversified_generator().compress_to_field()?,
+                self.debtor.transmission_key().compress_to_field()?,
+                self.debtor.clue_key(),
+                creditor,
+            ),
+        )?;
+
+        Ok(StateCommitmentVar { inner: commitment })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::note::test_note;
+    use ark_relations::r1cs::ConstraintSystem;
+    use penumbra_keys::Address;
+    use rand::thread_rng;
+
+    #[test]
+    fn native_and_r1cs_commitments_match() {
+        let mut rng = thread_rng();
+        let note = test_note(Address::dummy(&mut rng), Address::dummy(&mut rng));
+
+        for version in [CommitmentVersion::V1, CommitmentVersion::V2] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let note_var = NoteVar::new_witness(cs.clone(), || Ok(note.clone())).unwrap();
+            let commitment_var = note_var.commit_with_version(version).unwrap();
+
+            assert_eq!(commitment_var.value().unwrap(), note.commit_with_version(version));
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
 }
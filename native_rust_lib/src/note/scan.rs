@@ -0,0 +1,66 @@
+use penumbra_keys::keys::{AddressIndex, IncomingViewingKey};
+use rayon::prelude::*;
+
+use crate::note::Note;
+
+/// A note found while scanning a batch of ciphertexts.
+#[derive(Clone, Debug)]
+pub struct ScannedNote {
+    /// Position of the ciphertext in the scanned batch.
+    pub position: usize,
+    pub note: Note,
+    /// The index of the wallet address the note was sent to.
+    pub address_index: AddressIndex,
+}
+
+/// Trial-decrypts every ciphertext in parallel and returns the notes addressed
+/// to any diversified address of `ivk`, in batch order.
+pub fn scan_notes(ivk: &IncomingViewingKey, ciphertexts: &[Vec<u8>]) -> Vec<ScannedNote> {
+    ciphertexts
+        .par_iter()
+        .enumerate()
+        .filter_map(|(position, ciphertext)| {
+            let note = Note::decrypt(ciphertext, ivk).ok()?;
+            let address_index = ivk.index_for_diversifier(note.creditor().diversifier());
+            Some(ScannedNote {
+                position,
+                note,
+                address_index,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::note::test_note;
+    use penumbra_keys::keys::{Bip44Path, SeedPhrase, SpendKey};
+    use penumbra_keys::Address;
+    use rand::thread_rng;
+
+    #[test]
+    fn scan_finds_notes_for_any_address_index() {
+        let mut rng = thread_rng();
+        let spend_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let ivk = spend_key.full_viewing_key().incoming();
+
+        let mut note_to = |creditor: Address| {
+            test_note(Address::dummy(&mut rng), creditor).encrypt().unwrap()
+        };
+
+        let ciphertexts = vec![
+            note_to(Address::dummy(&mut thread_rng())),
+            note_to(ivk.payment_address(2u32.into()).0),
+            note_to(ivk.payment_address(7u32.into()).0),
+        ];
+
+        let scanned = scan_notes(ivk, &ciphertexts);
+        let found: Vec<_> = scanned
+            .iter()
+            .map(|s| (s.position, s.address_index.account))
+            .collect();
+        assert_eq!(found, vec![(1, 2), (2, 7)]);
+    }
+}
@@ -0,0 +1,201 @@
+use ark_ff::ToConstraintField;
+use ark_groth16::{r1cs_to_qap::LibsnarkReduction, Groth16, Proof};
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use decaf377::{Bls12_377, Fq, Fr};
+use decaf377_rdsa::{SpendAuth, VerificationKey};
+use once_cell::sync::OnceCell;
+use penumbra_keys::address::AddressVar;
+use penumbra_keys::keys::{
+    AuthorizationKeyVar, IncomingViewingKeyVar, NullifierKey, NullifierKeyVar,
+    RandomizedVerificationKey, SpendAuthRandomizerVar,
+};
+use penumbra_keys::Address;
+
+use super::{Circuit, CircuitKeys, GROTH16_PROOF_LENGTH_BYTES};
+
+pub(super) static AUTHORIZATION_KEYS: CircuitKeys = CircuitKeys {
+    circuit: Circuit::Authorization,
+    #[cfg(any(test, feature = "dev-parameters"))]
+    dev_setup: || super::dev_parameters(AuthorizationCircuit::dummy(), b"pocketlib.proof.authorization"),
+    proving_key: OnceCell::new(),
+    verifying_key: OnceCell::new(),
+};
+
+/// Public inputs of an authorization proof.
+#[derive(Clone, Debug)]
+pub struct AuthorizationProofPublic {
+    /// The randomized verification key the note was signed with.
+    pub rk: VerificationKey<SpendAuth>,
+    /// The debtor address recorded in the note.
+    pub debtor: Address,
+}
+
+/// Witness of an authorization proof.
+#[derive(Clone)]
+pub struct AuthorizationProofPrivate {
+    /// The debtor's spend verification key.
+    pub ak: VerificationKey<SpendAuth>,
+    /// The debtor's nullifier key, needed to derive their incoming viewing key.
+    pub nk: NullifierKey,
+    /// The randomizer turning `ak` into `rk`.
+    pub spend_auth_randomizer: Fr,
+}
+
+impl std::fmt::Debug for AuthorizationProofPrivate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthorizationProofPrivate")
+            .field("ak", &self.ak)
+            .field("nk", &"<redacted>")
+            .field("spend_auth_randomizer", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Proves that `rk` is a randomization of the spend authorization key
+/// behind the debtor address, without revealing which key that is.
+#[derive(Clone, Debug)]
+pub struct AuthorizationCircuit {
+    public: AuthorizationProofPublic,
+    private: AuthorizationProofPrivate,
+}
+
+impl AuthorizationCircuit {
+    fn new(public: AuthorizationProofPublic, private: AuthorizationProofPrivate) -> Self {
+        Self { public, private }
+    }
+
+    /// A circuit with the right shape for the setup; its values are irrelevant.
+    #[cfg(any(test, feature = "dev-parameters"))]
+    fn dummy() -> Self {
+        use ark_ff::UniformRand;
+        use penumbra_keys::keys::{Bip44Path, SeedPhrase, SpendKey};
+
+        let mut rng = rand::thread_rng();
+        let spend_key = SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let fvk = spend_key.full_viewing_key();
+        let spend_auth_randomizer = Fr::rand(&mut rng);
+        let rk = spend_key.spend_auth_key().randomize(&spend_auth_randomizer).into();
+        let (debtor, _) = fvk.incoming().payment_address(0u32.into());
+
+        Self::new(
+            AuthorizationProofPublic { rk, debtor },
+            AuthorizationProofPrivate {
+                ak: *fvk.spend_verification_key(),
+                nk: *fvk.nullifier_key(),
+                spend_auth_randomizer,
+            },
+        )
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for AuthorizationCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> ark_relations::r1cs::Result<()> {
+        // Witnesses
+        let ak_element_var = AuthorizationKeyVar::new_witness(cs.clone(), || Ok(self.private.ak))?;
+        let nk_var = NullifierKeyVar::new_witness(cs.clone(), || Ok(self.private.nk))?;
+        let spend_auth_randomizer_var = SpendAuthRandomizerVar::new_witness(cs.clone(), || {
+            Ok(self.private.spend_auth_randomizer)
+        })?;
+
+        // Public inputs
+        let rk_var = RandomizedVerificationKey::new_input(cs.clone(), || Ok(self.public.rk))?;
+        let debtor_var = AddressVar::new_input(cs, || Ok(self.public.debtor.clone()))?;
+
+        // Randomized verification key integrity
+        let computed_rk_var = ak_element_var.randomize(&spend_auth_randomizer_var)?;
+        computed_rk_var.enforce_equal(&rk_var)?;
+
+        // Debtor address integrity: the transmission key must come from the
+        // incoming viewing key derived from ak and nk.
+        let ivk = IncomingViewingKeyVar::derive(&nk_var, &ak_element_var)?;
+        let computed_transmission_key = ivk.diversified_public(&debtor_var.diversified_generator)?;
+        computed_transmission_key.enforce_equal(&debtor_var.transmission_key)?;
+
+        Ok(())
+    }
+}
+
+/// A serialized Groth16 proof that a randomized key belongs to the debtor.
+#[derive(Clone, Debug)]
+pub struct AuthorizationProof([u8; GROTH16_PROOF_LENGTH_BYTES]);
+
+impl AuthorizationProof {
+    pub fn prove(
+        blinding_r: Fq,
+        blinding_s: Fq,
+        public: AuthorizationProofPublic,
+        private: AuthorizationProofPrivate,
+    ) -> anyhow::Result<Self> {
+        let pk = AUTHORIZATION_KEYS.proving_key()?;
+        let circuit = AuthorizationCircuit::new(public, private);
+        let proof = Groth16::<Bls12_377, LibsnarkReduction>::create_proof_with_reduction(
+            circuit, pk, blinding_r, blinding_s,
+        )?;
+
+        let mut proof_bytes = [0u8; GROTH16_PROOF_LENGTH_BYTES];
+        proof.serialize_compressed(&mut proof_bytes[..])?;
+        Ok(Self(proof_bytes))
+    }
+
+    pub fn verify(&self, public: AuthorizationProofPublic) -> anyhow::Result<()> {
+        self.verify_with(&AUTHORIZATION_KEYS, public)
+    }
+
+    pub(crate) fn verify_with(&self, keys: &CircuitKeys, public: AuthorizationProofPublic) -> anyhow::Result<()> {
+        let vk = keys.verifying_key()?;
+        let proof = Proof::deserialize_compressed(&self.0[..])?;
+
+        let mut public_inputs = Vec::new();
+        let element_rk = decaf377::Encoding(public.rk.to_bytes())
+            .vartime_decompress()
+            .map_err(|_| anyhow::anyhow!("could not decompress element points"))?;
+        public_inputs.extend(
+            element_rk
+                .to_field_elements()
+                .ok_or_else(|| anyhow::anyhow!("rk is not a valid field element"))?,
+        );
+        public_inputs.extend(
+            public
+                .debtor
+                .to_field_elements()
+                .ok_or_else(|| anyhow::anyhow!("debtor address is not a valid field element"))?,
+        );
+
+        Groth16::<Bls12_377, LibsnarkReduction>::verify_with_processed_vk(
+            vk,
+            public_inputs.as_slice(),
+            &proof,
+        )?
+        .then_some(())
+        .ok_or_else(|| anyhow::anyhow!("proof did not verify"))
+    }
+
+    pub fn to_bytes(&self) -> [u8; GROTH16_PROOF_LENGTH_BYTES] {
+        self.0
+    }
+}
+
+impl TryFrom<&[u8]> for AuthorizationProof {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(bytes.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_witness() {
+        let AuthorizationCircuit { private, .. } = AuthorizationCircuit::dummy();
+
+        let debug = format!("{private:?}");
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(&hex::encode(private.nk.0.to_bytes())));
+    }
+}
@@ -0,0 +1,238 @@
+use ark_ff::ToConstraintField;
+use ark_groth16::{r1cs_to_qap::LibsnarkReduction, Groth16, Proof};
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use decaf377::{Bls12_377, Fq, Fr};
+use decaf377_rdsa::{SpendAuth, VerificationKey};
+use once_cell::sync::OnceCell;
+use penumbra_keys::keys::{
+    AuthorizationKeyVar, IncomingViewingKeyVar, NullifierKey, NullifierKeyVar,
+    RandomizedVerificationKey, SpendAuthRandomizerVar,
+};
+use penumbra_tct::r1cs::StateCommitmentVar;
+use penumbra_tct::StateCommitment;
+
+use super::{Circuit, CircuitKeys, GROTH16_PROOF_LENGTH_BYTES};
+use crate::note::r1cs::NoteVar;
+use crate::note::Note;
+
+pub(super) static INTENT_KEYS: CircuitKeys = CircuitKeys {
+    circuit: Circuit::Intent,
+    #[cfg(any(test, feature = "dev-parameters"))]
+    dev_setup: || super::dev_parameters(IntentCircuit::dummy(), b"pocketlib.proof.intent"),
+    proving_key: OnceCell::new(),
+    verifying_key: OnceCell::new(),
+};
+
+/// Public inputs of an intent proof.
+#[derive(Clone, Debug)]
+pub struct IntentProofPublic {
+    /// The commitment of the encrypted note.
+    pub note_commitment: StateCommitment,
+    /// The randomized verification key the commitment was signed with.
+    pub rk: VerificationKey<SpendAuth>,
+}
+
+/// Witness of an intent proof.
+#[derive(Clone)]
+pub struct IntentProofPrivate {
+    /// The note behind the public commitment.
+    pub note: Note,
+    /// The debtor's spend verification key.
+    pub ak: VerificationKey<SpendAuth>,
+    /// The debtor's nullifier key, needed to derive their incoming viewing key.
+    pub nk: NullifierKey,
+    /// The randomizer turning `ak` into `rk`.
+    pub spend_auth_randomizer: Fr,
+}
+
+impl std::fmt::Debug for IntentProofPrivate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntentProofPrivate")
+            .field("note", &self.note)
+            .field("ak", &self.ak)
+            .field("nk", &"<redacted>")
+            .field("spend_auth_randomizer", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Proves knowledge of the note behind a commitment, and that `rk` is a
+/// randomization of the spend authorization key behind that note's debtor
+/// address, without revealing the note or the debtor.
+#[derive(Clone, Debug)]
+pub struct IntentCircuit {
+    public: IntentProofPublic,
+    private: IntentProofPrivate,
+}
+
+impl IntentCircuit {
+    fn new(public: IntentProofPublic, private: IntentProofPrivate) -> Self {
+        Self { public, private }
+    }
+
+    /// A circuit with the right shape for the setup; its values are irrelevant.
+    #[cfg(any(test, feature = "dev-parameters"))]
+    fn dummy() -> Self {
+        use ark_ff::UniformRand;
+        use penumbra_asset::{asset, Value};
+        use penumbra_keys::keys::{Bip44Path, SeedPhrase, SpendKey};
+        use penumbra_keys::Address;
+        use penumbra_shielded_pool::Rseed;
+
+        let mut rng = rand::thread_rng();
+        let spend_key = SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(&mut rng), &Bip44Path::new(0));
+        let fvk = spend_key.full_viewing_key();
+        let spend_auth_randomizer = Fr::rand(&mut rng);
+        let rk = spend_key.spend_auth_key().randomize(&spend_auth_randomizer).into();
+        let (debtor, _) = fvk.incoming().payment_address(0u32.into());
+        let note = Note::from_parts(
+            debtor,
+            Address::dummy(&mut rng),
+            Value {
+                amount: 0u64.into(),
+                asset_id: asset::Id(Fq::from(0u64)),
+            },
+            Rseed::generate(&mut rng),
+        )
+        .expect("dummy addresses are distinct");
+
+        Self::new(
+            IntentProofPublic {
+                note_commitment: note.commit(),
+                rk,
+            },
+            IntentProofPrivate {
+                note,
+                ak: *fvk.spend_verification_key(),
+                nk: *fvk.nullifier_key(),
+                spend_auth_randomizer,
+            },
+        )
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for IntentCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> ark_relations::r1cs::Result<()> {
+        // Witnesses
+        let note_var = NoteVar::new_witness(cs.clone(), || Ok(self.private.note.clone()))?;
+        let ak_element_var = AuthorizationKeyVar::new_witness(cs.clone(), || Ok(self.private.ak))?;
+        let nk_var = NullifierKeyVar::new_witness(cs.clone(), || Ok(self.private.nk))?;
+        let spend_auth_randomizer_var = SpendAuthRandomizerVar::new_witness(cs.clone(), || {
+            Ok(self.private.spend_auth_randomizer)
+        })?;
+
+        // Public inputs
+        let claimed_note_commitment =
+            StateCommitmentVar::new_input(cs.clone(), || Ok(self.public.note_commitment))?;
+        let rk_var = RandomizedVerificationKey::new_input(cs, || Ok(self.public.rk))?;
+
+        // Note commitment integrity
+        let note_commitment = note_var.commit()?;
+        note_commitment.enforce_equal(&claimed_note_commitment)?;
+
+        // Randomized verification key integrity
+        let computed_rk_var = ak_element_var.randomize(&spend_auth_randomizer_var)?;
+        computed_rk_var.enforce_equal(&rk_var)?;
+
+        // Debtor address integrity: the note's debtor transmission key must
+        // come from the incoming viewing key derived from ak and nk.
+        let ivk = IncomingViewingKeyVar::derive(&nk_var, &ak_element_var)?;
+        let computed_transmission_key = ivk.diversified_public(&note_var.diversified_generator())?;
+        computed_transmission_key.enforce_equal(&note_var.transmission_key())?;
+
+        Ok(())
+    }
+}
+
+/// A serialized Groth16 proof that an intent's rk belongs to the debtor of
+/// the committed note.
+#[derive(Clone, Debug)]
+pub struct IntentProof([u8; GROTH16_PROOF_LENGTH_BYTES]);
+
+impl IntentProof {
+    pub fn prove(
+        blinding_r: Fq,
+        blinding_s: Fq,
+        public: IntentProofPublic,
+        private: IntentProofPrivate,
+    ) -> anyhow::Result<Self> {
+        let pk = INTENT_KEYS.proving_key()?;
+        let circuit = IntentCircuit::new(public, private);
+        let proof = Groth16::<Bls12_377, LibsnarkReduction>::create_proof_with_reduction(
+            circuit, pk, blinding_r, blinding_s,
+        )?;
+
+        let mut proof_bytes = [0u8; GROTH16_PROOF_LENGTH_BYTES];
+        proof.serialize_compressed(&mut proof_bytes[..])?;
+        Ok(Self(proof_bytes))
+    }
+
+    pub fn verify(&self, public: IntentProofPublic) -> anyhow::Result<()> {
+        let vk = INTENT_KEYS.verifying_key()?;
+        let proof = Proof::deserialize_compressed(&self.0[..])?;
+
+        let mut public_inputs = Vec::new();
+        public_inputs.extend(
+            public
+                .note_commitment
+                .0
+                .to_field_elements()
+                .ok_or_else(|| anyhow::anyhow!("note commitment is not a valid field element"))?,
+        );
+        let element_rk = decaf377::Encoding(public.rk.to_bytes())
+            .vartime_decompress()
+            .map_err(|_| anyhow::anyhow!("could not decompress element points"))?;
+        public_inputs.extend(
+            element_rk
+                .to_field_elements()
+                .ok_or_else(|| anyhow::anyhow!("rk is not a valid field element"))?,
+        );
+
+        Groth16::<Bls12_377, LibsnarkReduction>::verify_with_processed_vk(
+            vk,
+            public_inputs.as_slice(),
+            &proof,
+        )?
+        .then_some(())
+        .ok_or_else(|| anyhow::anyhow!("proof did not verify"))
+    }
+
+    pub fn to_bytes(&self) -> [u8; GROTH16_PROOF_LENGTH_BYTES] {
+        self.0
+    }
+}
+
+impl TryFrom<&[u8]> for IntentProof {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(bytes.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn rk_is_bound_to_the_committed_debtor() {
+        crate::proof::load_dev_parameters();
+        let mut rng = rand::thread_rng();
+        let IntentCircuit { public, private } = IntentCircuit::dummy();
+
+        let proof = IntentProof::prove(Fq::rand(&mut rng), Fq::rand(&mut rng), public.clone(), private)
+            .expect("can create proof");
+        assert!(proof.verify(public.clone()).is_ok());
+
+        // Someone else's rk does not verify against this commitment
+        let other = IntentProofPublic {
+            rk: IntentCircuit::dummy().public.rk,
+            ..public
+        };
+        assert!(proof.verify(other).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use ark_groth16::{prepare_verifying_key, PreparedVerifyingKey, ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use decaf377::Bls12_377;
+use once_cell::sync::OnceCell;
+
+pub use penumbra_proof_params::GROTH16_PROOF_LENGTH_BYTES;
+
+mod authorization;
+mod intent;
+mod note_opening;
+
+pub use authorization::{
+    AuthorizationCircuit, AuthorizationProof, AuthorizationProofPrivate, AuthorizationProofPublic,
+};
+pub use intent::{IntentCircuit, IntentProof, IntentProofPrivate, IntentProofPublic};
+pub use note_opening::{
+    NoteOpeningCircuit, NoteOpeningProof, NoteOpeningProofPrivate, NoteOpeningProofPublic,
+};
+
+/// The circuits this crate proves statements with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Circuit {
+    NoteOpening,
+    Authorization,
+    Intent,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParameterError {
+    #[error("{0:?} proving key is not loaded")]
+    MissingProvingKey(Circuit),
+    #[error("{0:?} verifying key is not loaded")]
+    MissingVerifyingKey(Circuit),
+    #[error("invalid {0:?} parameters: {1}")]
+    Invalid(Circuit, String),
+}
+
+impl Circuit {
+    pub(crate) fn keys(self) -> &'static CircuitKeys {
+        match self {
+            Circuit::NoteOpening => &note_opening::NOTE_OPENING_KEYS,
+            Circuit::Authorization => &authorization::AUTHORIZATION_KEYS,
+            Circuit::Intent => &intent::INTENT_KEYS,
+        }
+    }
+
+    /// Loads the compressed proving key produced by the circuit's setup
+    /// ceremony. Its verifying key is loaded along with it.
+    pub fn load_proving_key(self, bytes: &[u8]) -> Result<(), ParameterError> {
+        let pk = ProvingKey::<Bls12_377>::deserialize_compressed(bytes)
+            .map_err(|e| ParameterError::Invalid(self, e.to_string()))?;
+        let keys = self.keys();
+        keys.set_verifying_key(pk.vk.clone())?;
+
+        if let Err(pk) = keys.proving_key.set(pk) {
+            if keys.proving_key.get().expect("already loaded").vk != pk.vk {
+                return Err(ParameterError::Invalid(self, "a different proving key is loaded".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the compressed verifying key, which is all a verifier needs.
+    pub fn load_verifying_key(self, bytes: &[u8]) -> Result<(), ParameterError> {
+        let vk = VerifyingKey::<Bls12_377>::deserialize_compressed(bytes)
+            .map_err(|e| ParameterError::Invalid(self, e.to_string()))?;
+
+        self.keys().set_verifying_key(vk)
+    }
+
+    /// The compressed proving key of the development setup, for tests that
+    /// load keys the way a release build does.
+    #[cfg(test)]
+    pub(crate) fn dev_proving_key(self) -> Vec<u8> {
+        use ark_serialize::CanonicalSerialize;
+
+        let mut bytes = Vec::new();
+        (self.keys().dev_setup)()
+            .serialize_compressed(&mut bytes)
+            .expect("can serialize proving key");
+        bytes
+    }
+
+    /// Keys of this circuit as they are before anything is loaded, for tests
+    /// that must not see the keys other tests load into the statics.
+    #[cfg(test)]
+    pub(crate) fn unloaded_keys(self) -> CircuitKeys {
+        CircuitKeys {
+            circuit: self,
+            dev_setup: self.keys().dev_setup,
+            proving_key: OnceCell::new(),
+            verifying_key: OnceCell::new(),
+        }
+    }
+}
+
+/// Loads the development keys of every circuit, once per test process.
+#[cfg(test)]
+pub(crate) fn load_dev_parameters() {
+    static LOADED: std::sync::Once = std::sync::Once::new();
+    LOADED.call_once(|| {
+        for circuit in [Circuit::NoteOpening, Circuit::Authorization, Circuit::Intent] {
+            circuit
+                .load_proving_key(&circuit.dev_proving_key())
+                .expect("dev parameters load");
+        }
+    });
+}
+
+/// Groth16 keys of one circuit, loaded from the output of its setup ceremony.
+///
+/// The `dev-parameters` feature falls back to [`dev_parameters`] when nothing
+/// was loaded. Proofs made with those keys can be forged. Tests load those
+/// keys explicitly instead, so they exercise the same path as a release build.
+pub(crate) struct CircuitKeys {
+    circuit: Circuit,
+    #[cfg(any(test, feature = "dev-parameters"))]
+    dev_setup: fn() -> ProvingKey<Bls12_377>,
+    proving_key: OnceCell<ProvingKey<Bls12_377>>,
+    verifying_key: OnceCell<PreparedVerifyingKey<Bls12_377>>,
+}
+
+impl CircuitKeys {
+    pub(crate) fn proving_key(&self) -> Result<&ProvingKey<Bls12_377>, ParameterError> {
+        #[cfg(feature = "dev-parameters")]
+        self.dev_fallback();
+
+        self.proving_key
+            .get()
+            .ok_or(ParameterError::MissingProvingKey(self.circuit))
+    }
+
+    pub(crate) fn verifying_key(&self) -> Result<&PreparedVerifyingKey<Bls12_377>, ParameterError> {
+        #[cfg(feature = "dev-parameters")]
+        self.dev_fallback();
+
+        self.verifying_key
+            .get()
+            .ok_or(ParameterError::MissingVerifyingKey(self.circuit))
+    }
+
+    fn set_verifying_key(&self, vk: VerifyingKey<Bls12_377>) -> Result<(), ParameterError> {
+        if self.verifying_key.get_or_init(|| prepare_verifying_key(&vk)).vk != vk {
+            return Err(ParameterError::Invalid(
+                self.circuit,
+                "a different verifying key is loaded".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "dev-parameters")]
+    fn dev_fallback(&self) {
+        if self.proving_key.get().is_none() && self.verifying_key.get().is_none() {
+            let pk = self.proving_key.get_or_init(self.dev_setup);
+            self.verifying_key.get_or_init(|| prepare_verifying_key(&pk.vk));
+        }
+    }
+}
+
+/// Runs the circuit-specific Groth16 setup from a seed derived from `domain`.
+///
+/// For development only. The seed is public, so anyone can recompute the
+/// setup trapdoor and forge proofs for any public inputs.
+#[cfg(any(test, feature = "dev-parameters"))]
+fn dev_parameters<C: ark_relations::r1cs::ConstraintSynthesizer<decaf377::Fq>>(
+    circuit: C,
+    domain: &[u8],
+) -> ProvingKey<Bls12_377> {
+    use ark_groth16::Groth16;
+    use ark_snark::CircuitSpecificSetupSNARK;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    let seed = blake2b_simd::Params::new().hash_length(32).hash(domain);
+    let seed: [u8; 32] = seed.as_bytes().try_into().expect("hash is 32 bytes");
+    let mut rng = ChaCha20Rng::from_seed(seed);
+
+    let (pk, _) = Groth16::<Bls12_377>::circuit_specific_setup(circuit, &mut rng)
+        .expect("can perform circuit specific setup");
+
+    pk
+}
@@ -0,0 +1,175 @@
+use ark_ff::ToConstraintField;
+use ark_groth16::{r1cs_to_qap::LibsnarkReduction, Groth16, Proof};
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use decaf377::{Bls12_377, Fq};
+use once_cell::sync::OnceCell;
+use penumbra_tct::r1cs::StateCommitmentVar;
+use penumbra_tct::StateCommitment;
+
+use super::{Circuit, CircuitKeys, GROTH16_PROOF_LENGTH_BYTES};
+use crate::note::r1cs::NoteVar;
+use crate::note::Note;
+
+pub(super) static NOTE_OPENING_KEYS: CircuitKeys = CircuitKeys {
+    circuit: Circuit::NoteOpening,
+    #[cfg(any(test, feature = "dev-parameters"))]
+    dev_setup: || super::dev_parameters(NoteOpeningCircuit::dummy(), b"pocketlib.proof.note_opening"),
+    proving_key: OnceCell::new(),
+    verifying_key: OnceCell::new(),
+};
+
+/// Public inputs of a note opening proof.
+#[derive(Clone, Debug)]
+pub struct NoteOpeningProofPublic {
+    /// The commitment the prover claims to be able to open.
+    pub note_commitment: StateCommitment,
+}
+
+/// Witness of a note opening proof.
+#[derive(Clone, Debug)]
+pub struct NoteOpeningProofPrivate {
+    /// The note behind the public commitment.
+    pub note: Note,
+}
+
+/// Proves knowledge of a note whose commitment equals a public state commitment.
+#[derive(Clone, Debug)]
+pub struct NoteOpeningCircuit {
+    public: NoteOpeningProofPublic,
+    private: NoteOpeningProofPrivate,
+}
+
+impl NoteOpeningCircuit {
+    fn new(public: NoteOpeningProofPublic, private: NoteOpeningProofPrivate) -> Self {
+        Self { public, private }
+    }
+
+    /// A circuit with the right shape for the setup; its values are irrelevant.
+    #[cfg(any(test, feature = "dev-parameters"))]
+    fn dummy() -> Self {
+        use penumbra_asset::{asset, Value};
+        use penumbra_keys::Address;
+        use penumbra_shielded_pool::Rseed;
+
+        let mut rng = rand::thread_rng();
+        let note = Note::from_parts(
+            Address::dummy(&mut rng),
+            Address::dummy(&mut rng),
+            Value {
+                amount: 0u64.into(),
+                asset_id: asset::Id(Fq::from(0u64)),
+            },
+            Rseed::generate(&mut rng),
+        )
+        .expect("dummy addresses are distinct");
+
+        Self::new(
+            NoteOpeningProofPublic {
+                note_commitment: note.commit(),
+            },
+            NoteOpeningProofPrivate { note },
+        )
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for NoteOpeningCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> ark_relations::r1cs::Result<()> {
+        // Witnesses
+        let note_var = NoteVar::new_witness(cs.clone(), || Ok(self.private.note.clone()))?;
+
+        // Public inputs
+        let claimed_note_commitment =
+            StateCommitmentVar::new_input(cs, || Ok(self.public.note_commitment))?;
+
+        // Note commitment integrity
+        let note_commitment = note_var.commit()?;
+        note_commitment.enforce_equal(&claimed_note_commitment)?;
+
+        Ok(())
+    }
+}
+
+/// A serialized Groth16 proof that the prover can open a note commitment.
+#[derive(Clone, Debug)]
+pub struct NoteOpeningProof([u8; GROTH16_PROOF_LENGTH_BYTES]);
+
+impl NoteOpeningProof {
+    pub fn prove(
+        blinding_r: Fq,
+        blinding_s: Fq,
+        public: NoteOpeningProofPublic,
+        private: NoteOpeningProofPrivate,
+    ) -> anyhow::Result<Self> {
+        let pk = NOTE_OPENING_KEYS.proving_key()?;
+        let circuit = NoteOpeningCircuit::new(public, private);
+        let proof = Groth16::<Bls12_377, LibsnarkReduction>::create_proof_with_reduction(
+            circuit, pk, blinding_r, blinding_s,
+        )?;
+
+        let mut proof_bytes = [0u8; GROTH16_PROOF_LENGTH_BYTES];
+        proof.serialize_compressed(&mut proof_bytes[..])?;
+        Ok(Self(proof_bytes))
+    }
+
+    pub fn verify(&self, public: NoteOpeningProofPublic) -> anyhow::Result<()> {
+        let vk = NOTE_OPENING_KEYS.verifying_key()?;
+        let proof = Proof::deserialize_compressed(&self.0[..])?;
+
+        let mut public_inputs = Vec::new();
+        public_inputs.extend(
+            public
+                .note_commitment
+                .0
+                .to_field_elements()
+                .ok_or_else(|| anyhow::anyhow!("note commitment is not a valid field element"))?,
+        );
+
+        Groth16::<Bls12_377, LibsnarkReduction>::verify_with_processed_vk(
+            vk,
+            public_inputs.as_slice(),
+            &proof,
+        )?
+        .then_some(())
+        .ok_or_else(|| anyhow::anyhow!("proof did not verify"))
+    }
+
+    pub fn to_bytes(&self) -> [u8; GROTH16_PROOF_LENGTH_BYTES] {
+        self.0
+    }
+}
+
+impl TryFrom<&[u8]> for NoteOpeningProof {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(bytes.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn note_opening_proof_roundtrip() {
+        crate::proof::load_dev_parameters();
+        let mut rng = rand::thread_rng();
+        let NoteOpeningCircuit { public, private } = NoteOpeningCircuit::dummy();
+
+        let proof = NoteOpeningProof::prove(
+            Fq::rand(&mut rng),
+            Fq::rand(&mut rng),
+            public.clone(),
+            private,
+        )
+        .expect("can create proof");
+        assert!(proof.verify(public).is_ok());
+
+        let other = NoteOpeningCircuit::dummy().public;
+        assert!(proof.verify(other).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use ark_ff::UniformRand;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use decaf377::{Fq, Fr};
+use decaf377_rdsa::{Signature, SpendAuth, VerificationKey};
+use penumbra_keys::keys::SpendKey;
+use penumbra_keys::Address;
+use rand::rngs::OsRng;
+
+use crate::secret::{sign_randomized, Secret};
+use crate::note::encoding::{address_from_bytes, address_to_bytes, ADDRESS_LEN_BYTES};
+use crate::proof::{
+    AuthorizationProof, AuthorizationProofPrivate, AuthorizationProofPublic, Circuit,
+    CircuitKeys, ParameterError, GROTH16_PROOF_LENGTH_BYTES,
+};
+
+/// Scheme of payment request URIs.
+pub const URI_SCHEME: &str = "pocketlib:";
+/// The only payment request version this library writes.
+pub const VERSION: u8 = 1;
+/// Longest memo, in bytes, a payment request may carry.
+pub const MAX_MEMO_LEN_BYTES: usize = 256;
+
+/// rk || signature || authorization proof
+const AUTH_LEN_BYTES: usize = 32 + 64 + GROTH16_PROOF_LENGTH_BYTES;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("payment request is malformed: {0}")]
+    Malformed(&'static str),
+    #[error("unsupported payment request version {0}")]
+    UnsupportedVersion(u8),
+    #[error("memo is longer than {MAX_MEMO_LEN_BYTES} bytes")]
+    MemoTooLong,
+    #[error("signing key does not control the creditor address")]
+    NotCreditor,
+    #[error("payment request signature is invalid")]
+    InvalidSignature,
+    #[error("payment request expired")]
+    Expired,
+    #[error("could not prove authorization: {0}")]
+    ProofGenerationFailed(String),
+    #[error(transparent)]
+    Parameters(#[from] ParameterError),
+}
+
+/// A creditor's request to be issued an IOU.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentRequest {
+    pub creditor: Address,
+    pub amount: u64,
+    pub asset_id: u64,
+    pub memo: String,
+    /// Unix time in seconds after which the request is void, or 0 for never.
+    pub expires_at: u64,
+}
+
+/// A payment request signed by the key behind its creditor address.
+///
+/// The signature is made with a randomized key, and the authorization proof
+/// ties that key to the creditor address without revealing the creditor's
+/// spend verification key.
+#[derive(Clone, Debug)]
+pub struct SignedPaymentRequest {
+    pub request: PaymentRequest,
+    rk: VerificationKey<SpendAuth>,
+    signature: Signature<SpendAuth>,
+    proof: AuthorizationProof,
+}
+
+impl PaymentRequest {
+    /// The compact encoding of the unsigned request, which is what gets signed.
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        let memo = self.memo.as_bytes();
+        if memo.len() > MAX_MEMO_LEN_BYTES {
+            return Err(Error::MemoTooLong);
+        }
+
+        let mut bytes = Vec::with_capacity(1 + ADDRESS_LEN_BYTES + 3 * 8 + 2 + memo.len());
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&address_to_bytes(&self.creditor));
+        bytes.extend_from_slice(&self.amount.to_le_bytes());
+        bytes.extend_from_slice(&self.asset_id.to_le_bytes());
+        bytes.extend_from_slice(&self.expires_at.to_le_bytes());
+        bytes.extend_from_slice(&(memo.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(memo);
+        Ok(bytes)
+    }
+
+    /// Signs the request with the spend key controlling the creditor address.
+    pub fn sign(self, spend_key: &SpendKey) -> Result<SignedPaymentRequest, Error> {
+        let fvk = spend_key.full_viewing_key();
+        if !fvk.incoming().views_address(&self.creditor) {
+            return Err(Error::NotCreditor);
+        }
+
+        let randomizer = Secret(Fr::rand(&mut OsRng));
+        let rk = fvk.spend_verification_key().randomize(&randomizer);
+        let signature = sign_randomized(spend_key, &randomizer, &self.encode()?);
+        let proof = AuthorizationProof::prove(
+            Fq::rand(&mut OsRng),
+            Fq::rand(&mut OsRng),
+            AuthorizationProofPublic {
+                rk,
+                debtor: self.creditor.clone(),
+            },
+            AuthorizationProofPrivate {
+                ak: *fvk.spend_verification_key(),
+                nk: *fvk.nullifier_key(),
+                spend_auth_randomizer: *randomizer,
+            },
+        )
+        .map_err(|e| match e.downcast::<ParameterError>() {
+            Ok(e) => Error::Parameters(e),
+            Err(e) => Error::ProofGenerationFailed(e.to_string()),
+        })?;
+
+        Ok(SignedPaymentRequest {
+            request: self,
+            rk,
+            signature,
+            proof,
+        })
+    }
+}
+
+impl SignedPaymentRequest {
+    /// Checks the signature, the authorization proof and, given the current
+    /// Unix time, the expiry.
+    pub fn verify(&self, now: u64) -> Result<(), Error> {
+        self.verify_with(Circuit::Authorization.keys(), now)
+    }
+
+    /// As [`verify`](Self::verify), against the given authorization keys.
+    /// Missing keys are reported as such, not as a bad signature.
+    pub(crate) fn verify_with(&self, keys: &CircuitKeys, now: u64) -> Result<(), Error> {
+        self.rk
+            .verify(&self.request.encode()?, &self.signature)
+            .map_err(|_| Error::InvalidSignature)?;
+        self.proof
+            .verify_with(
+                keys,
+                AuthorizationProofPublic {
+                    rk: self.rk,
+                    debtor: self.request.creditor.clone(),
+                },
+            )
+            .map_err(|e| match e.downcast::<ParameterError>() {
+                Ok(e) => Error::Parameters(e),
+                Err(_) => Error::InvalidSignature,
+            })?;
+
+        if self.request.expires_at != 0 && now > self.request.expires_at {
+            return Err(Error::Expired);
+        }
+        Ok(())
+    }
+
+    /// The compact binary form, suited to a single QR code.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = self.request.encode()?;
+        bytes.extend_from_slice(&self.rk.to_bytes());
+        bytes.extend_from_slice(&<[u8; 64]>::from(self.signature));
+        bytes.extend_from_slice(&self.proof.to_bytes());
+        Ok(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        let version = reader.take::<1>()?[0];
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        let creditor = address_from_bytes(&reader.take::<ADDRESS_LEN_BYTES>()?)
+            .ok_or(Error::Malformed("invalid creditor address"))?;
+        let amount = u64::from_le_bytes(reader.take::<8>()?);
+        let asset_id = u64::from_le_bytes(reader.take::<8>()?);
+        let expires_at = u64::from_le_bytes(reader.take::<8>()?);
+        let memo_len = u16::from_le_bytes(reader.take::<2>()?) as usize;
+        if memo_len > MAX_MEMO_LEN_BYTES {
+            return Err(Error::MemoTooLong);
+        }
+        let memo = String::from_utf8(reader.take_slice(memo_len)?.to_vec())
+            .map_err(|_| Error::Malformed("memo is not UTF-8"))?;
+
+        let auth = reader.take_slice(AUTH_LEN_BYTES)?;
+        if !reader.0.is_empty() {
+            return Err(Error::Malformed("trailing bytes"));
+        }
+        let rk = VerificationKey::try_from(&auth[..32])
+            .map_err(|_| Error::Malformed("invalid randomized key"))?;
+        let signature = Signature::try_from(&auth[32..96])
+            .map_err(|_| Error::Malformed("invalid signature"))?;
+        let proof = AuthorizationProof::try_from(&auth[96..])
+            .map_err(|_| Error::Malformed("invalid proof"))?;
+
+        Ok(Self {
+            request: PaymentRequest {
+                creditor,
+                amount,
+                asset_id,
+                memo,
+                expires_at,
+            },
+            rk,
+            signature,
+            proof,
+        })
+    }
+
+    /// `pocketlib:` followed by the URL-safe base64 of the compact binary form.
+    pub fn to_uri(&self) -> Result<String, Error> {
+        Ok(format!("{URI_SCHEME}{}", URL_SAFE_NO_PAD.encode(self.encode()?)))
+    }
+
+    pub fn from_uri(uri: &str) -> Result<Self, Error> {
+        let payload = uri
+            .trim()
+            .strip_prefix(URI_SCHEME)
+            .ok_or(Error::Malformed("not a pocketlib URI"))?;
+        let bytes = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| Error::Malformed("invalid base64"))?;
+        Self::decode(&bytes)
+    }
+}
+
+/// Reads fixed-size fields off the front of a byte slice.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::Malformed("truncated"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take_slice(N)?.try_into().expect("slice has length N"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use penumbra_keys::keys::{Bip44Path, SeedPhrase};
+
+    #[test]
+    fn signed_request_roundtrips_through_uri() {
+        crate::proof::load_dev_parameters();
+        let spend_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(OsRng), &Bip44Path::new(0));
+        let (creditor, _) = spend_key.full_viewing_key().incoming().payment_address(1u32.into());
+
+        let request = PaymentRequest {
+            creditor,
+            amount: 30,
+            asset_id: 1,
+            memo: "lunch".to_string(),
+            expires_at: 1_000,
+        };
+        let signed = request.clone().sign(&spend_key).unwrap();
+
+        let parsed = SignedPaymentRequest::from_uri(&signed.to_uri().unwrap()).unwrap();
+        assert_eq!(parsed.request, request);
+        assert!(parsed.verify(999).is_ok());
+        assert!(matches!(parsed.verify(1_001), Err(Error::Expired)));
+
+        // Any change to the request breaks the signature
+        let mut tampered = parsed;
+        tampered.request.amount = 3_000;
+        assert!(matches!(tampered.verify(999), Err(Error::InvalidSignature)));
+    }
+
+    #[cfg(not(feature = "dev-parameters"))]
+    #[test]
+    fn missing_verifying_key_is_not_an_invalid_signature() {
+        crate::proof::load_dev_parameters();
+        let spend_key =
+            SpendKey::from_seed_phrase_bip44(SeedPhrase::generate(OsRng), &Bip44Path::new(0));
+        let (creditor, _) = spend_key.full_viewing_key().incoming().payment_address(1u32.into());
+
+        let signed = PaymentRequest {
+            creditor,
+            amount: 30,
+            asset_id: 1,
+            memo: String::new(),
+            expires_at: 0,
+        }
+        .sign(&spend_key)
+        .unwrap();
+
+        let unloaded = Circuit::Authorization.unloaded_keys();
+        assert!(matches!(
+            signed.verify_with(&unloaded, 0),
+            Err(Error::Parameters(ParameterError::MissingVerifyingKey(Circuit::Authorization)))
+        ));
+    }
+}
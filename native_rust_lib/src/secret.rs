@@ -0,0 +1,34 @@
+use decaf377::Fr;
+use decaf377_rdsa::{Signature, SpendAuth};
+use penumbra_keys::keys::SpendKey;
+use rand::rngs::OsRng;
+
+/// Signs `message` with the spend authorization key of `spend_key` randomized by `randomizer`.
+pub fn sign_randomized(spend_key: &SpendKey, randomizer: &Fr, message: &[u8]) -> Signature<SpendAuth> {
+    let randomized = Secret(spend_key.spend_auth_key().randomize(randomizer));
+    randomized.sign(OsRng, message)
+}
+
+/// A flat secret, such as a field element or a signing key, wiped on drop.
+///
+/// decaf377 and penumbra do not implement `Zeroize` for their scalars and
+/// keys, so this overwrites the value in place. It only covers this copy:
+/// values copied out of it, e.g. into a proof witness, are not wiped.
+pub(crate) struct Secret<T: Copy>(pub(crate) T);
+
+impl<T: Copy> std::ops::Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Copy> Drop for Secret<T> {
+    fn drop(&mut self) {
+        // SAFETY: `T` is only ever a scalar or a key made of plain limbs and
+        // bytes, with no references or heap data, and it is not read again.
+        unsafe { zeroize::zeroize_flat_type(&mut self.0) }
+    }
+}
+